@@ -1 +1,3 @@
-foo
+FILE "Mega Man X 4 (USA).bin" BINARY
+  TRACK 01 MODE2/2352
+    INDEX 01 00:00:00
@@ -0,0 +1,45 @@
+use crate::prelude::*;
+use std::io::{self, Read, Seek, SeekFrom};
+use zip::ZipArchive;
+
+pub fn is_zip(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase() == "zip")
+        .unwrap_or(false)
+}
+
+// the ROM is assumed to be the largest file in the archive
+pub fn largest_entry<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<usize> {
+    let mut largest: Option<(usize, u64)> = None;
+
+    for i in 0..archive.len() {
+        let file = archive.by_index(i)?;
+        if file.is_dir() {
+            continue;
+        }
+        if largest.map_or(true, |(_, size)| file.size() > size) {
+            largest = Some((i, file.size()));
+        }
+    }
+
+    largest.map(|(i, _)| i).context("empty zip")
+}
+
+// reads up to len bytes starting at offset, looking inside .zip files
+pub fn read_at(path: &Path, offset: u64, len: usize) -> Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    let mut buffer = Vec::with_capacity(len);
+
+    if is_zip(path) {
+        let mut archive = ZipArchive::new(file)?;
+        let i = largest_entry(&mut archive)?;
+        let mut entry = archive.by_index(i)?;
+        io::copy(&mut entry.by_ref().take(offset), &mut io::sink())?;
+        entry.take(len as u64).read_to_end(&mut buffer)?;
+    } else {
+        file.seek(SeekFrom::Start(offset))?;
+        file.take(len as u64).read_to_end(&mut buffer)?;
+    }
+
+    Ok(buffer)
+}
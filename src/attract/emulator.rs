@@ -0,0 +1,53 @@
+use super::ARTWORKS;
+use crate::collection::Collection;
+use crate::prelude::*;
+use std::fmt::Write as _;
+
+// pegasus launch placeholders, and what Attract-Mode calls them
+static PLACEHOLDERS: [(&str, &str); 6] = [
+    ("{file.path}", "\"[romfilename]\""),
+    ("{file.uri}", "\"[romfilename]\""),
+    ("{file.name}", "\"[name][romext]\""),
+    ("{file.basename}", "\"[name]\""),
+    ("{file.dir}", "\"[rompath]\""),
+    ("{file}", "\"[romfilename]\""),
+];
+
+pub fn to_str(collection: &Collection, artwork_dir: &Path, has_folders: bool) -> Result<String> {
+    let mut buf = String::new();
+
+    let launch = PLACEHOLDERS
+        .iter()
+        .fold(collection.launch.clone(), |launch, (from, to)| {
+            launch.replace(from, to)
+        });
+    let (executable, args) = match launch.trim().split_once(' ') {
+        Some((executable, args)) => (executable.to_owned(), args.trim().to_owned()),
+        None => (launch.trim().to_owned(), String::new()),
+    };
+
+    let mut extensions: Vec<String> = collection
+        .extensions
+        .iter()
+        .map(|e| format!(".{}", e))
+        .collect();
+    if collection.m3u {
+        extensions.push(".m3u".to_owned());
+    }
+    // matches game folders, such as PS3 ones
+    if has_folders {
+        extensions.push("<DIR>".to_owned());
+    }
+
+    writeln!(buf, "# Generated by louvre")?;
+    writeln!(buf, "{:<20} {}", "executable", executable)?;
+    writeln!(buf, "{:<20} {}", "args", args)?;
+    writeln!(buf, "{:<20} {}", "rompath", collection.path.to_string())?;
+    writeln!(buf, "{:<20} {}", "romext", extensions.join(";"))?;
+    for (label, _) in ARTWORKS {
+        let path = artwork_dir.join(label);
+        writeln!(buf, "{:<10} {:<9} {}", "artwork", label, path.to_string())?;
+    }
+
+    Ok(buf)
+}
@@ -0,0 +1,11 @@
+pub mod emulator;
+pub mod romlist;
+
+use crate::provider::ImageKind;
+
+// artwork labels, as used by Attract-Mode layouts
+pub static ARTWORKS: [(&str, ImageKind); 3] = [
+    ("flyer", ImageKind::BoxFront),
+    ("fanart", ImageKind::Background),
+    ("snap", ImageKind::Screenshot),
+];
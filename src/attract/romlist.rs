@@ -0,0 +1,81 @@
+use crate::collection::Collection;
+use crate::meta::Meta;
+use crate::prelude::*;
+use chrono::prelude::*;
+
+pub static HEADER: &str = "#Name;Title;Emulator;CloneOf;Year;Manufacturer;Category;Players;Rotation;Control;Status;DisplayCount;DisplayType;AltRomname;AltTitle;Extra;Buttons;Series;Language;Region;Rating";
+
+// the name Attract-Mode matches against files in the rompath: the path relative
+// to it, without the extension, as roms can be in subfolders or be folders
+pub fn name(meta: &Meta, collection: &Collection) -> String {
+    if collection.path.join(&meta.file).is_dir() {
+        meta.file.to_string()
+    } else {
+        meta.file.with_extension("").to_string()
+    }
+}
+
+pub fn to_str(meta: &Meta, collection: &Collection) -> Result<String> {
+    let game = &meta.game;
+
+    let year = match game.release_date {
+        Some(secs) => {
+            let naive = NaiveDateTime::from_timestamp_opt(secs, 0).context("invalid date")?;
+            naive.year().to_string()
+        }
+        None => String::new(),
+    };
+
+    let manufacturer = if game.publishers.is_empty() {
+        &game.developers
+    } else {
+        &game.publishers
+    };
+
+    let (languages, regions) = match &meta.title {
+        Some(title) => (title.languages.join(","), title.regions.join(",")),
+        None => (String::new(), String::new()),
+    };
+
+    let fields = [
+        name(meta, collection),
+        game.name.clone(),
+        collection.name.clone(),
+        String::new(),
+        year,
+        manufacturer.join(", "),
+        game.genres.join(" / "),
+        game.players.map(|x| x.to_string()).unwrap_or_default(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        languages,
+        regions,
+        String::new(),
+    ];
+
+    let fields: Vec<String> = fields.iter().map(|f| field(f)).collect();
+    Ok(fields.join(";"))
+}
+
+// the format has no escaping
+fn field(text: &str) -> String {
+    text.replace(';', ",").replace(['\n', '\r'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field() {
+        assert_eq!(field("Sonic; Knuckles\nEdition"), "Sonic, Knuckles Edition");
+    }
+}
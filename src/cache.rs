@@ -0,0 +1,179 @@
+use crate::config;
+use crate::hash;
+use crate::prelude::*;
+use std::fs;
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime};
+
+static FOLDER_NAME: &str = "cache";
+static TMP_EXTENSION: &str = "tmp";
+
+// tells apart the temp files of concurrent writes
+static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+// API responses on disk, named after the hash of their url and body
+pub struct Cache {
+    dir: PathBuf,
+    ttl: Duration,
+    max_size: u64, // bytes
+    offline: bool,
+    reads: AtomicBool,
+    writes: bool,
+}
+
+impl Cache {
+    pub fn new(config: &Config) -> Result<Self> {
+        let dir = config::default_dir()?.join(FOLDER_NAME);
+        let yaml = &config.yaml.cache;
+        let enabled = !config.cli.no_cache;
+        let offline = config.cli.offline;
+        // offline, stale data beats no data
+        let ttl = if offline {
+            Duration::MAX
+        } else {
+            Duration::from_secs(u64::from(yaml.ttl_days) * 24 * 60 * 60)
+        };
+
+        let cache = Self {
+            dir,
+            ttl,
+            max_size: u64::from(yaml.max_size_mb) * 1024 * 1024,
+            offline,
+            reads: AtomicBool::new(enabled),
+            writes: enabled,
+        };
+
+        if cache.writes && !offline {
+            cache.prune()?;
+        }
+
+        Ok(cache)
+    }
+
+    // e.g. when refreshing, which is meant to fetch new data; offline, the cache is all there is
+    pub fn bypass_reads(&self) {
+        if !self.offline {
+            self.reads.store(false, Ordering::Relaxed);
+        }
+    }
+
+    pub fn get(&self, url: &str, body: &str) -> Option<String> {
+        if !self.reads.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        let path = self.path(url, body).ok()?;
+        let age = fs::metadata(&path).ok()?.modified().ok()?.elapsed().ok()?;
+        if age > self.ttl {
+            return None;
+        }
+
+        let text = fs::read_to_string(path).ok()?;
+        trace!(msg = "cache hit", url);
+        Some(text)
+    }
+
+    // written to a temp file first, so that concurrent reads never see half of it
+    pub fn set(&self, url: &str, body: &str, text: &str) -> Result<()> {
+        if !self.writes {
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path(url, body)?;
+        let tmp_path = path.with_extension(format!(
+            "{}.{}.{}",
+            process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed),
+            TMP_EXTENSION
+        ));
+        fs::write(&tmp_path, text)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    fn path(&self, url: &str, body: &str) -> Result<PathBuf> {
+        let key = format!("{}\n{}", url, body);
+        let hashes = hash::from_reader(key.as_bytes())?;
+        Ok(self.dir.join(hashes.sha1))
+    }
+
+    // removes expired entries, then the oldest ones until under the size limit
+    fn prune(&self) -> Result<()> {
+        if !self.dir.exists() {
+            return Ok(());
+        }
+
+        let mut entries: Vec<(SystemTime, u64, PathBuf)> = vec![];
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            let modified = metadata.modified()?;
+            let is_expired = matches!(modified.elapsed(), Ok(age) if age > self.ttl);
+            if is_expired {
+                fs::remove_file(entry.path())?;
+            } else {
+                entries.push((modified, metadata.len(), entry.path()));
+            }
+        }
+
+        entries.sort();
+        let mut size: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        for (_, len, path) in entries {
+            if size <= self.max_size {
+                break;
+            }
+            fs::remove_file(path)?;
+            size -= len;
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for Cache {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(name: &str, max_size: u64) -> Cache {
+        let dir = std::env::temp_dir().join(format!("louvre-{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        Cache {
+            dir,
+            ttl: Duration::from_secs(60),
+            max_size,
+            offline: false,
+            reads: AtomicBool::new(true),
+            writes: true,
+        }
+    }
+
+    #[test]
+    fn test_get_set() {
+        let cache = cache("test_get_set", 1024);
+        assert_eq!(cache.get("/v4/games", "search \"Tetris\";"), None);
+
+        cache.set("/v4/games", "search \"Tetris\";", "[]").unwrap();
+        assert_eq!(
+            cache.get("/v4/games", "search \"Tetris\";").as_deref(),
+            Some("[]")
+        );
+        assert_eq!(cache.get("/v4/games", "search \"Doom\";"), None);
+        assert_eq!(cache.get("/v5/games", "search \"Tetris\";"), None);
+        // no temp files are left behind
+        assert_eq!(fs::read_dir(&cache.dir).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_prune() {
+        let cache = cache("test_prune", 10);
+        cache.set("/v4/games", "1", "123456").unwrap();
+        cache.set("/v4/games", "2", "123456").unwrap();
+
+        cache.prune().unwrap();
+        assert_eq!(fs::read_dir(&cache.dir).unwrap().count(), 1);
+    }
+}
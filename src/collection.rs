@@ -1,6 +1,12 @@
+use crate::descriptor;
+use crate::disc;
+use crate::header;
 use crate::platform::Platform;
 use crate::prelude::*;
-use walkdir::DirEntry;
+use crate::provider;
+use crate::retroarch;
+use crate::title;
+use walkdir::{DirEntry, WalkDir};
 
 #[derive(Debug)]
 pub enum Status {
@@ -10,6 +16,8 @@ pub enum Status {
     NoMetadata,
     Directory,
     Denylist(String),
+    Track,
+    MissingTracks(Vec<PathBuf>),
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -19,13 +27,81 @@ pub struct Collection {
     pub path: PathBuf,
     pub extensions: Vec<String>,
     pub platform: Platform,
+    #[serde(default)]
+    pub provider: provider::Kind,
     pub denylist: Option<Vec<String>>,
+    pub dats: Option<Vec<PathBuf>>,
+    #[serde(default)]
+    pub m3u: bool,
+    pub title_rules: Option<Vec<title::Rule>>,
     pub title_map: Option<HashMap<String, String>>,
+    pub id_map: Option<HashMap<String, u32>>,
+    pub retroarch_core: Option<retroarch::Core>,
     pub launch: String,
 }
 
 impl Collection {
-    pub fn should_include(&self, entry: &DirEntry) -> Status {
+    // track files referenced by the descriptors in this collection
+    pub fn tracks(&self, entries: &[DirEntry]) -> HashSet<PathBuf> {
+        entries
+            .iter()
+            .map(|e| e.path())
+            .filter(|p| descriptor::is_descriptor(p) && self.has_extension(p))
+            .filter_map(|p| descriptor::tracks(p).ok())
+            .flatten()
+            .collect()
+    }
+
+    // every file in the collection, relative to its path, with its status;
+    // files inside game folders are replaced by the folder itself
+    pub fn scan(&self) -> Result<Vec<(PathBuf, Status)>> {
+        let entries: Vec<_> = WalkDir::new(&self.path)
+            .max_depth(6)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .collect();
+
+        let tracks = self.tracks(&entries);
+
+        entries
+            .iter()
+            .map(|entry| {
+                let status = self.should_include(entry, &tracks);
+                let path = entry.path();
+                let path = header::ps3::game_dir(path).unwrap_or(path);
+                Ok((path.strip_prefix(&self.path)?.to_owned(), status))
+            })
+            .collect()
+    }
+
+    // what gets scraped, with disc sets represented by their first disc
+    pub fn roms(&self) -> Result<Vec<PathBuf>> {
+        let mut roms: Vec<PathBuf> = self
+            .scan()?
+            .into_iter()
+            // missing tracks are reported when processing
+            .filter(|(_, status)| {
+                matches!(status, Status::ShouldInclude | Status::MissingTracks(_))
+            })
+            .map(|(path, _)| path)
+            .collect();
+
+        roms.sort();
+        roms.dedup();
+        Ok(disc::group(roms))
+    }
+
+    // igdb game id pinned for a rom, relative to the collection path
+    pub fn pinned_id(&self, rom: &Path) -> Option<u32> {
+        self.id_map.as_ref()?.get(&rom.to_string()).copied()
+    }
+
+    fn has_extension(&self, path: &Path) -> bool {
+        let extension = path.extension().unwrap_or_default().to_string_lossy();
+        self.extensions.contains(&extension.to_lowercase())
+    }
+
+    pub fn should_include(&self, entry: &DirEntry, tracks: &HashSet<PathBuf>) -> Status {
         use Status::*;
 
         let path = entry.path();
@@ -61,6 +137,19 @@ impl Collection {
                                     }
                                 }
 
+                                if tracks.contains(path) {
+                                    return Track;
+                                }
+
+                                if descriptor::is_descriptor(path) {
+                                    match descriptor::missing_tracks(path) {
+                                        Ok(missing) if !missing.is_empty() => {
+                                            return MissingTracks(missing)
+                                        }
+                                        _ => (),
+                                    }
+                                }
+
                                 ShouldInclude
                             } else {
                                 Unsupported
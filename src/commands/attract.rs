@@ -0,0 +1,96 @@
+use crate::attract::{emulator, romlist, ARTWORKS};
+use crate::export;
+use crate::meta;
+use crate::prelude::*;
+use clap::Args;
+use clap::Subcommand;
+use std::fs;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum SubCmd {
+    Gen {
+        /// Attract-Mode folder, containing romlists and emulators
+        #[clap(long)]
+        dir: PathBuf,
+
+        /// Symlink artwork instead of copying it
+        #[clap(long)]
+        symlink: bool,
+    },
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct Input {
+    #[clap(subcommand)]
+    subcmd: SubCmd,
+}
+
+impl Input {
+    fn handle_gen(&self, system: System, dir: &Path, symlink: bool) -> Result<()> {
+        let collections = &system.config.yaml.collections;
+        let get_collection = |i: usize| collections.get(i).expect("invalid collection index");
+
+        let filepath_map = meta::get_filepaths(collections)?;
+
+        for (collection_index, meta_files) in filepath_map {
+            let collection = get_collection(collection_index);
+            // artwork is looked up by rom name, unlike the media in meta
+            let artwork_dir = dir.join("scraper").join(&collection.name);
+            let mut lines = vec![romlist::HEADER.to_owned()];
+            let mut has_folders = false;
+
+            for meta_file in meta_files {
+                let meta = meta::get(&meta_file)?;
+
+                let rom_path = collection.path.join(&meta.file);
+                if !rom_path.exists() {
+                    continue;
+                }
+                has_folders |= rom_path.is_dir();
+
+                lines.push(romlist::to_str(&meta, collection)?);
+
+                for (label, kind) in ARTWORKS {
+                    let image_path = meta::image_path(&meta_file, kind);
+                    if !image_path.exists() {
+                        continue;
+                    }
+
+                    let extension = image_path.extension().unwrap_or_default().to_string();
+                    let target = artwork_dir.join(label).join(format!(
+                        "{}.{}",
+                        romlist::name(&meta, collection),
+                        extension
+                    ));
+                    export::place(&image_path, &target, symlink)?;
+                }
+            }
+
+            if lines.len() == 1 {
+                continue;
+            }
+
+            let romlists_dir = dir.join("romlists");
+            fs::create_dir_all(&romlists_dir)?;
+            let txt = format!("{}\n", lines.join("\n"));
+            fs::write(romlists_dir.join(format!("{}.txt", collection.name)), txt)?;
+
+            let emulators_dir = dir.join("emulators");
+            fs::create_dir_all(&emulators_dir)?;
+            let cfg = emulator::to_str(collection, &artwork_dir, has_folders)?;
+            fs::write(emulators_dir.join(format!("{}.cfg", collection.name)), cfg)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Runnable for Input {
+    fn run(&self, system: System) -> Result<()> {
+        match &self.subcmd {
+            SubCmd::Gen { dir, symlink } => self.handle_gen(system, dir, *symlink),
+        }
+    }
+}
+
+impl HasDeps for Input {}
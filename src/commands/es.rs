@@ -0,0 +1,69 @@
+use crate::es::gamelist;
+use crate::meta;
+use crate::prelude::*;
+use clap::Args;
+use clap::Subcommand;
+use std::fs;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum SubCmd {
+    Gen,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct Input {
+    #[clap(subcommand)]
+    subcmd: SubCmd,
+}
+
+impl Input {
+    fn handle_gen(&self, system: System) -> Result<()> {
+        let collections = &system.config.yaml.collections;
+        let get_collection = |i: usize| collections.get(i).expect("invalid collection index");
+
+        let filepath_map = meta::get_filepaths(collections)?;
+
+        for (collection_index, meta_files) in filepath_map {
+            let collection = get_collection(collection_index);
+            let mut entries = vec![];
+
+            for meta_file in meta_files {
+                let meta = meta::get(&meta_file)?;
+
+                if !collection.path.join(&meta.file).exists() {
+                    continue;
+                }
+
+                entries.push(gamelist::from_meta(meta, &meta_file, collection)?);
+            }
+
+            if entries.is_empty() {
+                continue;
+            }
+
+            let es_path = collection.es_path();
+            let previous = if es_path.exists() {
+                let xml = fs::read_to_string(&es_path)?;
+                gamelist::parse(&xml)
+                    .with_context(|| format!("unable to parse {}", es_path.to_string()))?
+            } else {
+                vec![]
+            };
+
+            let entries = gamelist::merge(entries, previous, &collection.path);
+            fs::write(es_path, gamelist::to_str(&entries)?)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Runnable for Input {
+    fn run(&self, system: System) -> Result<()> {
+        match self.subcmd {
+            SubCmd::Gen => self.handle_gen(system),
+        }
+    }
+}
+
+impl HasDeps for Input {}
@@ -0,0 +1,110 @@
+use crate::export;
+use crate::launchbox::{self, game};
+use crate::meta;
+use crate::prelude::*;
+use crate::provider::ImageKind;
+use clap::Args;
+use clap::Subcommand;
+use std::fs;
+
+static IMAGE_DIRS: [(&str, ImageKind); 3] = [
+    ("Box - Front", ImageKind::BoxFront),
+    ("Fanart - Background", ImageKind::Background),
+    ("Screenshot - Gameplay", ImageKind::Screenshot),
+];
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum SubCmd {
+    Gen {
+        /// LaunchBox folder, containing Data and Images
+        #[clap(long)]
+        dir: PathBuf,
+
+        /// Symlink images instead of copying them
+        #[clap(long)]
+        symlink: bool,
+    },
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct Input {
+    #[clap(subcommand)]
+    subcmd: SubCmd,
+}
+
+impl Input {
+    fn handle_gen(&self, system: System, dir: &Path, symlink: bool) -> Result<()> {
+        let collections = &system.config.yaml.collections;
+        let get_collection = |i: usize| collections.get(i).expect("invalid collection index");
+
+        let mut filepath_map: Vec<_> = meta::get_filepaths(collections)?.into_iter().collect();
+        filepath_map.sort_by_key(|(collection_index, _)| *collection_index);
+
+        // collections of the same platform share a platform file and image folders
+        let mut games_buffers: HashMap<String, String> = HashMap::new();
+        // LaunchBox numbers the images of games with the same title
+        let mut image_counts: HashMap<(String, String), usize> = HashMap::new();
+
+        for (collection_index, meta_files) in filepath_map {
+            let collection = get_collection(collection_index);
+            let platform = collection.launchbox_platform();
+            let images_dir = dir.join("Images").join(&platform);
+            let games_buffer = games_buffers.entry(platform.clone()).or_default();
+
+            for meta_file in meta_files {
+                let meta = meta::get(&meta_file)?;
+
+                if !collection.path.join(&meta.file).exists() {
+                    continue;
+                }
+
+                games_buffer.push_str(&game::to_str(&meta, collection)?);
+
+                let title = launchbox::sanitize(&meta.game.name);
+                let count = image_counts
+                    .entry((platform.clone(), title.clone()))
+                    .or_default();
+                *count += 1;
+
+                for (image_dir, kind) in IMAGE_DIRS {
+                    let image_path = meta::image_path(&meta_file, kind);
+                    if !image_path.exists() {
+                        continue;
+                    }
+
+                    let extension = image_path.extension().unwrap_or_default().to_string();
+                    let target = images_dir
+                        .join(image_dir)
+                        .join(format!("{}-{:02}.{}", title, count, extension));
+                    export::place(&image_path, &target, symlink)?;
+                }
+            }
+        }
+
+        for (platform, games_buffer) in games_buffers {
+            if games_buffer.is_empty() {
+                continue;
+            }
+
+            let platforms_dir = dir.join("Data").join("Platforms");
+            fs::create_dir_all(&platforms_dir)?;
+            let xml = format!(
+                "<?xml version=\"1.0\" standalone=\"yes\"?>\n<LaunchBox>\n{}</LaunchBox>\n",
+                games_buffer
+            );
+            fs::write(platforms_dir.join(format!("{}.xml", platform)), xml)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Runnable for Input {
+    fn run(&self, system: System) -> Result<()> {
+        match &self.subcmd {
+            SubCmd::Gen { dir, symlink } => self.handle_gen(system, dir, *symlink),
+        }
+    }
+}
+
+impl HasDeps for Input {}
@@ -1,8 +1,7 @@
 use crate::collection::Collection;
-use crate::igdb;
-use crate::igdb::Game;
 use crate::meta;
 use crate::prelude::*;
+use crate::provider::{self, Game, ImageKind, MetadataProvider};
 
 use crate::worker::{self, FileWorker, WorkerResult};
 use clap::Args;
@@ -24,10 +23,16 @@ impl FileWorker for Input {
         meta::get_filepaths(collections)
     }
 
-    fn process(collection: &Collection, file: &Path, system: &System) -> Result<WorkerResult> {
-        let game = meta::get(file)?.igdb;
+    fn process(
+        &self,
+        collection: &Collection,
+        file: &Path,
+        system: &System,
+    ) -> Result<WorkerResult> {
+        let meta = meta::get(file)?;
+        let provider = provider::get(system, meta.provider)?;
 
-        let progress = process_game(collection, file, &game, system.get::<igdb::Client>()?)?;
+        let progress = process_game(collection, file, &meta.game, provider)?;
         Ok(WorkerResult {
             entry: file.to_string_lossy().into(),
             progress,
@@ -39,7 +44,7 @@ fn process_game(
     collection: &Collection,
     meta_file: &Path,
     game: &Game,
-    igdb_client: &igdb::Client,
+    provider: &dyn MetadataProvider,
 ) -> Result<(u8, u8)> {
     let game_path = {
         let mut p = collection.path.to_owned();
@@ -51,36 +56,16 @@ fn process_game(
     let mut processed = 0;
     let delta = 33;
 
-    let image_definitions = vec![
-        (
-            game.cover.as_ref(),
-            "boxFront.jpg",
-            igdb::ImageSize::CoverBig,
-        ),
-        (
-            game.artworks.as_ref().and_then(|artworks| artworks.get(0)),
-            "background.jpg",
-            igdb::ImageSize::ScreenshotBig,
-        ),
-        (
-            game.screenshots
-                .as_ref()
-                .and_then(|artworks| artworks.get(0)),
-            "screenshot.jpg",
-            igdb::ImageSize::ScreenshotBig,
-        ),
-    ];
-
     let rom = meta_file.to_string_lossy().replace(meta::YAML_NAME, "");
 
-    for (image_option, image_name, image_size) in image_definitions {
-        if let Some(image) = image_option {
-            let image_path = game_path.join(format!("{}{}", rom, image_name));
+    for kind in ImageKind::ALL {
+        if let Some(image) = game.image(kind) {
+            let image_path = game_path.join(format!("{}{}", rom, kind.filename()));
             if image_path.exists() {
                 already_processed += delta;
             } else {
                 processed += delta;
-                igdb_client.download(image, image_size, true, &image_path)?;
+                provider.download(image, kind, &image_path)?;
             }
         }
     }
@@ -99,6 +84,6 @@ impl Runnable for Input {
 impl HasDeps for Input {
     fn deps(&self) -> HashSet<TypeId> {
         use crate::components::*;
-        [*TWITCH, *IGDB].into()
+        [*CACHE, *TWITCH, *IGDB, *TGDB, *DAT].into()
     }
 }
@@ -1,4 +1,10 @@
+pub mod attract;
 pub mod config;
+pub mod es;
+pub mod launchbox;
 pub mod media;
 pub mod pegasus;
+pub mod pin;
+pub mod report;
+pub mod retroarch;
 pub mod scrape;
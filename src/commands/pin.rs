@@ -0,0 +1,64 @@
+use crate::collection::Collection;
+use crate::commands::scrape;
+use crate::disc;
+use crate::header;
+use crate::prelude::*;
+use clap::Args;
+
+#[derive(Debug, Clone, Args)]
+pub struct Input {
+    /// ROM file or game folder
+    rom: PathBuf,
+    /// IGDB game id
+    id: u32,
+}
+
+impl Input {
+    // the collection containing the rom, and the rom relative to it
+    fn locate<'a>(&self, collections: &'a [Collection]) -> Result<(&'a Collection, PathBuf)> {
+        let rom = self
+            .rom
+            .canonicalize()
+            .with_context(|| format!("unable to find {}", self.rom.to_string()))?;
+        let rom = header::ps3::game_dir(&rom).unwrap_or(&rom);
+
+        for collection in collections {
+            let collection_path = match collection.path.canonicalize() {
+                Ok(path) => path,
+                Err(_) => continue,
+            };
+
+            if let Ok(relative) = rom.strip_prefix(&collection_path) {
+                // disc sets are scraped from their first disc
+                let first_disc = disc::siblings(&collection_path, relative)?
+                    .into_iter()
+                    .next();
+                return Ok((collection, first_disc.unwrap_or_else(|| relative.into())));
+            }
+        }
+
+        Err(anyhow!("{} is in no collection", self.rom.to_string()))
+    }
+}
+
+impl Runnable for Input {
+    fn run(&self, system: System) -> Result<()> {
+        let (collection, rom) = self.locate(&system.config.yaml.collections)?;
+
+        let options = scrape::Options {
+            pin: Some(self.id),
+            overwrite: true,
+            ..Default::default()
+        };
+        scrape::scrape_rom(collection, &rom, &system, &options)?;
+
+        Ok(())
+    }
+}
+
+impl HasDeps for Input {
+    fn deps(&self) -> HashSet<TypeId> {
+        use crate::components::*;
+        [*CACHE, *TWITCH, *IGDB, *DAT].into()
+    }
+}
@@ -0,0 +1,244 @@
+use crate::collection::{self, Collection};
+use crate::meta;
+use crate::prelude::*;
+use crate::provider::ImageKind;
+use clap::{ArgEnum, Args};
+use std::fmt;
+
+#[derive(ArgEnum, Debug, Clone, Copy)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct Input {
+    #[clap(long, arg_enum, default_value = "table")]
+    format: Format,
+
+    /// Matches with a confidence below this (0-1, 0.8 by default) are reported as low-confidence
+    #[clap(long)]
+    confidence_below: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum Status {
+    Matched,
+    Unmatched,
+    Denylisted,
+    LowConfidence,
+    MissingMedia,
+    OrphanedMeta,
+}
+
+impl Status {
+    const ALL: [Status; 6] = [
+        Status::Matched,
+        Status::Unmatched,
+        Status::Denylisted,
+        Status::LowConfidence,
+        Status::MissingMedia,
+        Status::OrphanedMeta,
+    ];
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        use Status::*;
+        let text = match self {
+            Matched => "matched",
+            Unmatched => "unmatched",
+            Denylisted => "denylisted",
+            LowConfidence => "low-confidence",
+            MissingMedia => "missing-media",
+            OrphanedMeta => "orphaned-meta",
+        };
+        fmt.write_str(text)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Row {
+    collection: String,
+    rom: PathBuf,
+    status: Status,
+    game: Option<String>,
+    confidence: Option<f32>,
+}
+
+impl Input {
+    fn rows(&self, collection: &Collection) -> Result<Vec<Row>> {
+        let mut rows = vec![];
+        let mut scraped = HashSet::new();
+
+        let row = |rom: PathBuf, status: Status| Row {
+            collection: collection.name.clone(),
+            rom,
+            status,
+            game: None,
+            confidence: None,
+        };
+
+        let meta_files = meta::get_filepaths(std::slice::from_ref(collection))?;
+        for meta_file in meta_files.into_values().flatten() {
+            let meta = meta::get(&meta_file)?;
+
+            scraped.insert(meta.file.clone());
+            scraped.extend(meta.discs.iter().flatten().cloned());
+
+            let is_confident = meta.is_confident(self.confidence_below);
+            let has_media = ImageKind::ALL.iter().all(|kind| {
+                meta.game.image(*kind).is_none() || meta::image_path(&meta_file, *kind).exists()
+            });
+
+            let status = if !collection.path.join(&meta.file).exists() {
+                Status::OrphanedMeta
+            } else if !is_confident {
+                Status::LowConfidence
+            } else if !has_media {
+                Status::MissingMedia
+            } else {
+                Status::Matched
+            };
+
+            rows.push(Row {
+                game: Some(meta.game.name),
+                confidence: meta.game.confidence,
+                ..row(meta.file, status)
+            });
+        }
+
+        for rom in collection.roms()? {
+            if !scraped.contains(&rom) {
+                rows.push(row(rom, Status::Unmatched));
+            }
+        }
+
+        for (path, status) in collection.scan()? {
+            if let collection::Status::Denylist(_) = status {
+                rows.push(row(path, Status::Denylisted));
+            }
+        }
+
+        rows.sort_by(|a, b| a.rom.cmp(&b.rom));
+        Ok(rows)
+    }
+}
+
+fn to_table(rows: &[Row]) -> String {
+    let header = ["COLLECTION", "STATUS", "CONFIDENCE", "ROM", "GAME"];
+    let lines: Vec<[String; 5]> = rows
+        .iter()
+        .map(|r| {
+            [
+                r.collection.clone(),
+                r.status.to_string(),
+                r.confidence
+                    .map(|c| format!("{:.2}", c))
+                    .unwrap_or_default(),
+                r.rom.to_string(),
+                r.game.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+
+    let mut widths = header.map(|h| h.chars().count());
+    for line in &lines {
+        for (width, cell) in widths.iter_mut().zip(line) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_line = |cells: &[&str]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect();
+        format!("{}\n", padded.join("  ").trim_end())
+    };
+
+    let mut txt = format_line(&header);
+    for line in &lines {
+        let cells: Vec<&str> = line.iter().map(|c| c.as_str()).collect();
+        txt.push_str(&format_line(&cells));
+    }
+
+    let counts: Vec<String> = Status::ALL
+        .iter()
+        .map(|status| {
+            let n = rows.iter().filter(|r| r.status == *status).count();
+            format!("{} {}", n, status)
+        })
+        .collect();
+    txt.push_str(&format!("\n{}\n", counts.join(", ")));
+
+    txt
+}
+
+fn to_csv(rows: &[Row]) -> String {
+    let mut txt = String::from("collection,status,confidence,rom,game\n");
+    for r in rows {
+        let cells = [
+            r.collection.clone(),
+            r.status.to_string(),
+            r.confidence.map(|c| c.to_string()).unwrap_or_default(),
+            r.rom.to_string(),
+            r.game.clone().unwrap_or_default(),
+        ];
+        let cells: Vec<String> = cells.iter().map(|c| csv_cell(c)).collect();
+        txt.push_str(&cells.join(","));
+        txt.push('\n');
+    }
+    txt
+}
+
+fn csv_cell(cell: &str) -> String {
+    if cell.contains([',', '"', '\n']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_owned()
+    }
+}
+
+impl Runnable for Input {
+    fn run(&self, system: System) -> Result<()> {
+        let mut rows = vec![];
+        for collection in &system.config.yaml.collections {
+            rows.append(&mut self.rows(collection)?);
+        }
+
+        let txt = match self.format {
+            Format::Table => to_table(&rows),
+            Format::Json => format!("{}\n", serde_json::to_string_pretty(&rows)?),
+            Format::Csv => to_csv(&rows),
+        };
+        print!("{}", txt);
+
+        Ok(())
+    }
+}
+
+impl HasDeps for Input {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_csv() {
+        let rows = [Row {
+            collection: "Game Boy".into(),
+            rom: "Pokemon - Red, Blue.gb".into(),
+            status: Status::LowConfidence,
+            game: Some("Pokémon \"Red\"".into()),
+            confidence: Some(0.5),
+        }];
+        assert_eq!(
+            to_csv(&rows),
+            "collection,status,confidence,rom,game\nGame Boy,low-confidence,0.5,\"Pokemon - Red, Blue.gb\",\"Pokémon \"\"Red\"\"\"\n"
+        );
+    }
+}
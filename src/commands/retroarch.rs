@@ -0,0 +1,82 @@
+use crate::meta;
+use crate::prelude::*;
+use crate::retroarch::{playlist, thumbnail};
+use clap::Args;
+use clap::Subcommand;
+use std::fs;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum SubCmd {
+    Gen {
+        /// RetroArch folder, containing playlists and thumbnails
+        #[clap(long)]
+        dir: PathBuf,
+
+        /// Symlink thumbnails instead of copying them
+        #[clap(long)]
+        symlink: bool,
+    },
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct Input {
+    #[clap(subcommand)]
+    subcmd: SubCmd,
+}
+
+impl Input {
+    fn handle_gen(&self, system: System, dir: &Path, symlink: bool) -> Result<()> {
+        let collections = &system.config.yaml.collections;
+        let get_collection = |i: usize| collections.get(i).expect("invalid collection index");
+
+        let mut filepath_map: Vec<_> = meta::get_filepaths(collections)?.into_iter().collect();
+        filepath_map.sort_by_key(|(collection_index, _)| *collection_index);
+
+        // collections of the same platform share a playlist
+        let mut playlists: HashMap<String, playlist::Playlist> = HashMap::new();
+
+        for (collection_index, meta_files) in filepath_map {
+            let collection = get_collection(collection_index);
+            let name = playlist::name(collection);
+            let thumbnails_dir = dir.join("thumbnails").join(&name);
+            let lpl = playlists
+                .entry(name)
+                .or_insert_with(|| playlist::Playlist::new(collection));
+
+            for meta_file in meta_files {
+                let meta = meta::get(&meta_file)?;
+
+                if !collection.path.join(&meta.file).exists() {
+                    continue;
+                }
+
+                let item = playlist::item(&meta, collection);
+                thumbnail::export(&thumbnails_dir, &meta_file, &item.label, symlink)?;
+                lpl.items.push(item);
+            }
+        }
+
+        for (name, lpl) in playlists {
+            if lpl.items.is_empty() {
+                continue;
+            }
+
+            let playlists_dir = dir.join("playlists");
+            fs::create_dir_all(&playlists_dir)?;
+            let txt = serde_json::to_string_pretty(&lpl)?;
+            fs::write(playlists_dir.join(format!("{}.lpl", name)), txt)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Runnable for Input {
+    fn run(&self, system: System) -> Result<()> {
+        match &self.subcmd {
+            SubCmd::Gen { dir, symlink } => self.handle_gen(system, dir, *symlink),
+        }
+    }
+}
+
+impl HasDeps for Input {}
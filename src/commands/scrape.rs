@@ -1,69 +1,208 @@
-use crate::collection::{self, Collection};
+use crate::cache::Cache;
+use crate::collection::Collection;
+use crate::dat;
+use crate::descriptor;
+use crate::disc;
+use crate::hash;
+use crate::header;
 use crate::igdb;
 use crate::meta::{self, Meta};
+use crate::platform::Platform;
 use crate::prelude::*;
+use crate::prompt;
+use crate::provider::{self, ImageKind};
 use crate::title;
 use crate::worker::{self, FileWorker, WorkerResult};
 use clap::Args;
 use dns_common::deser::to_yaml_str;
 use std::fs;
-use walkdir::WalkDir;
+use std::time::Duration;
+
+// results closer than this to the best one make it ambiguous
+static AMBIGUITY_MARGIN: f32 = 0.1;
 
 #[derive(Debug, Clone, Args)]
-pub struct Input {}
+pub struct Input {
+    /// Ask which IGDB result to use when there's no clear match
+    #[clap(long)]
+    interactive: bool,
+
+    /// Only scrape the collection with this name
+    #[clap(long)]
+    collection: Option<String>,
+
+    /// Scrape games that already have a meta file again
+    #[clap(long)]
+    refresh: bool,
+
+    /// Only refresh meta files older than this, e.g. 30d or 12h
+    #[clap(long, requires = "refresh", parse(try_from_str = parse_duration))]
+    older_than: Option<Duration>,
+
+    /// Only refresh games matched with a confidence below this (0-1, 0.8 if omitted)
+    #[clap(long, requires = "refresh")]
+    confidence_below: Option<Option<f32>>,
+
+    /// Only refresh ROMs whose path matches this glob, e.g. "Pokemon*"
+    #[clap(long, requires = "refresh", parse(try_from_str = glob_regex))]
+    glob: Option<Regex>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub interactive: bool,
+    pub pin: Option<u32>, // igdb game id to use instead of searching
+    pub overwrite: bool,  // replace an existing meta file
+    pub refresh: Option<Refresh>,
+}
+
+// which existing meta files to scrape again; all of them by default
+#[derive(Debug, Clone, Default)]
+pub struct Refresh {
+    pub older_than: Option<Duration>,
+    pub confidence_below: Option<Option<f32>>,
+    pub glob: Option<Regex>,
+}
+
+impl Refresh {
+    fn selects(&self, meta: &Meta, meta_path: &Path, rom: &Path) -> Result<bool> {
+        if let Some(older_than) = self.older_than {
+            let age = fs::metadata(meta_path)?
+                .modified()?
+                .elapsed()
+                .unwrap_or_default();
+            if age < older_than {
+                return Ok(false);
+            }
+        }
+
+        if let Some(threshold) = self.confidence_below {
+            if meta.is_confident(threshold) {
+                return Ok(false);
+            }
+        }
+
+        if let Some(glob) = &self.glob {
+            if !glob.is_match(&rom.to_string()) {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
 
 impl FileWorker for Input {
     fn get_filepaths(&self, collections: &[Collection]) -> Result<HashMap<usize, Vec<PathBuf>>> {
         let mut filepaths_map = HashMap::new();
 
+        if let Some(name) = &self.collection {
+            if !collections.iter().any(|c| &c.name == name) {
+                return Err(anyhow!("no collection named {}", name));
+            }
+        }
+
         for (from_index, collection) in collections.iter().enumerate() {
-            let collection_path = &collection.path;
-            let mut filepaths: Vec<PathBuf> = vec![];
-
-            let entries = WalkDir::new(collection_path)
-                .max_depth(6)
-                .into_iter()
-                .filter_map(|e| e.ok());
-
-            for entry in entries {
-                let status = collection.should_include(&entry);
-                if let collection::Status::ShouldInclude = status {
-                    let filepath = entry.path().strip_prefix(collection_path)?.to_owned();
-                    filepaths.push(filepath);
-                }
+            if matches!(&self.collection, Some(name) if name != &collection.name) {
+                continue;
             }
 
-            filepaths.sort();
-            filepaths_map.insert(from_index, filepaths);
+            filepaths_map.insert(from_index, collection.roms()?);
         }
 
         Ok(filepaths_map)
     }
 
-    fn process(collection: &Collection, file: &Path, system: &System) -> Result<WorkerResult> {
-        let title = title::prettify(file, &collection.title_map);
-        // dbg!(&title);
-        let igdb_client = system.get::<igdb::Client>()?;
-        let was_already_processed = process_title(collection, &title, file, igdb_client)?;
-        let progress = if was_already_processed {
-            (0, 100)
-        } else {
-            (100, 0)
+    fn process(
+        &self,
+        collection: &Collection,
+        file: &Path,
+        system: &System,
+    ) -> Result<WorkerResult> {
+        scrape_rom(collection, file, system, &self.options())
+    }
+}
+
+impl Input {
+    fn options(&self) -> Options {
+        let refresh = Refresh {
+            older_than: self.older_than,
+            confidence_below: self.confidence_below,
+            glob: self.glob.clone(),
         };
-        Ok(WorkerResult {
-            entry: title,
-            progress,
-        })
+        Options {
+            interactive: self.interactive,
+            refresh: Some(refresh).filter(|_| self.refresh),
+            ..Default::default()
+        }
+    }
+
+    // sends the IGDB searches ahead of time, in batches
+    fn prefetch(&self, system: &System) -> Result<()> {
+        let igdb = match system.get::<igdb::Client>() {
+            Ok(igdb) => igdb,
+            Err(_) => return Ok(()),
+        };
+
+        let options = self.options();
+        let collections = &system.config.yaml.collections;
+        let mut queries = vec![];
+
+        for (collection_index, roms) in self.get_filepaths(collections)? {
+            let collection = &collections[collection_index];
+            // titles from DATs are only known after hashing
+            if collection.provider != provider::Kind::Igdb || collection.dats.is_some() {
+                continue;
+            }
+
+            let normalizer =
+                title::Normalizer::for_collection(&system.config.yaml.title_rules, collection);
+            for rom in roms {
+                if let Some(title) = search_title(collection, &normalizer, &rom, &options)? {
+                    queries.push((title, collection.platform));
+                }
+            }
+        }
+
+        igdb.prefetch(&queries)
+    }
+}
+
+pub fn scrape_rom(
+    collection: &Collection,
+    file: &Path,
+    system: &System,
+    options: &Options,
+) -> Result<WorkerResult> {
+    let path = collection.path.join(file);
+    if descriptor::is_descriptor(&path) {
+        let missing = descriptor::missing_tracks(&path)?;
+        if !missing.is_empty() {
+            return Err(descriptor::MissingTracks(missing).into());
+        }
     }
+
+    let normalizer = title::Normalizer::for_collection(&system.config.yaml.title_rules, collection);
+    let title = normalizer.prettify(file);
+    // dbg!(&title);
+    let was_already_processed = process_title(collection, &normalizer, file, system, options)?;
+    let progress = if was_already_processed {
+        (0, 100)
+    } else {
+        (100, 0)
+    };
+    Ok(WorkerResult {
+        entry: title,
+        progress,
+    })
 }
 
-fn yaml_path(collection: &Collection, rom: &Path) -> PathBuf {
+fn yaml_path(collection: &Collection, name: &str) -> PathBuf {
     let mut p = collection.path.clone();
     p.push(meta::FOLDER_NAME);
     let filename = format!(
         "{}_{}",
-        rom.to_string_lossy()
-            .replace(|c: char| !c.is_alphanumeric(), "_"),
+        name.replace(|c: char| !c.is_alphanumeric(), "_"),
         meta::YAML_NAME
     )
     .replace("___", "_")
@@ -73,36 +212,387 @@ fn yaml_path(collection: &Collection, rom: &Path) -> PathBuf {
     p
 }
 
+// where a rom's meta file goes, and the files it stands for
+struct Location {
+    rom_path: PathBuf,
+    folder_header: Option<header::Header>,
+    discs: Vec<PathBuf>,
+    set: Option<PathBuf>,
+    playlist: Option<PathBuf>,
+    meta_path: PathBuf,
+}
+
+fn locate(collection: &Collection, rom: &Path) -> Result<Location> {
+    let rom_path = collection.path.join(rom);
+
+    // game folders are named after their PARAM.SFO instead
+    let folder_header = if rom_path.is_dir() {
+        read_header(collection, &rom_path)
+    } else {
+        None
+    };
+
+    // disc sets share a single entry, named after the set
+    let discs = disc::siblings(&collection.path, rom)?;
+    let set = disc::set_path(rom).filter(|_| discs.len() > 1);
+    let playlist = set
+        .as_deref()
+        .filter(|_| collection.m3u)
+        .map(disc::playlist_path);
+
+    let meta_name = match folder_header.as_ref().map(|h| (&h.code, &h.title)) {
+        Some((Some(code), Some(title))) => format!("{} {}", code, title),
+        _ => playlist
+            .as_deref()
+            .or(set.as_deref())
+            .unwrap_or(rom)
+            .to_string(),
+    };
+    let meta_path = yaml_path(collection, &meta_name);
+
+    Ok(Location {
+        rom_path,
+        folder_header,
+        discs,
+        set,
+        playlist,
+        meta_path,
+    })
+}
+
+enum Plan {
+    Skip,
+    New,
+    Again(Box<Meta>), // the previous meta, to tell what changed
+}
+
+fn plan(meta_path: &Path, rom: &Path, options: &Options) -> Result<Plan> {
+    if !meta_path.exists() {
+        return Ok(Plan::New);
+    }
+
+    if options.overwrite {
+        return Ok(match meta::get(meta_path) {
+            Ok(meta) => Plan::Again(Box::new(meta)),
+            Err(_) => Plan::New,
+        });
+    }
+
+    match &options.refresh {
+        Some(refresh) => {
+            let meta = meta::get(meta_path)?;
+            if refresh.selects(&meta, meta_path, rom)? {
+                Ok(Plan::Again(Box::new(meta)))
+            } else {
+                Ok(Plan::Skip)
+            }
+        }
+        None => Ok(Plan::Skip),
+    }
+}
+
+fn pin(
+    collection: &Collection,
+    rom: &Path,
+    options: &Options,
+    previous: Option<&Meta>,
+) -> Option<u32> {
+    let previous_pin = previous
+        .and_then(|m| m.pin.as_ref())
+        .and_then(|pin| pin.parse().ok());
+    options
+        .pin
+        .or_else(|| collection.pinned_id(rom))
+        .or(previous_pin)
+}
+
+// what process_title searches for, unless it won't search at all
+fn search_title(
+    collection: &Collection,
+    normalizer: &title::Normalizer,
+    rom: &Path,
+    options: &Options,
+) -> Result<Option<String>> {
+    let location = locate(collection, rom)?;
+
+    let previous = match plan(&location.meta_path, rom, options)? {
+        Plan::Skip => return Ok(None),
+        Plan::New => None,
+        Plan::Again(meta) => Some(meta),
+    };
+
+    if pin(collection, rom, options, previous.as_deref()).is_some() {
+        return Ok(None);
+    }
+
+    if descriptor::is_descriptor(&location.rom_path)
+        && !descriptor::missing_tracks(&location.rom_path)?.is_empty()
+    {
+        return Ok(None);
+    }
+
+    let header = match location.folder_header {
+        Some(header) => Some(header),
+        None => read_header(collection, &location.rom_path),
+    };
+
+    let header_title = header.as_ref().and_then(|h| h.title.as_deref());
+    Ok(Some(normalizer.resolve(rom, header_title)))
+}
+
 fn process_title(
     collection: &Collection,
-    title: &str,
+    normalizer: &title::Normalizer,
     rom: &Path,
-    igdb_client: &igdb::Client,
+    system: &System,
+    options: &Options,
 ) -> Result<bool> {
-    let meta_path = yaml_path(collection, rom);
+    let Location {
+        rom_path,
+        folder_header,
+        discs,
+        set,
+        playlist,
+        meta_path,
+    } = locate(collection, rom)?;
+    let is_folder = rom_path.is_dir();
 
-    if meta_path.exists() {
-        return Ok(true);
-    }
+    let previous = match plan(&meta_path, rom, options)? {
+        Plan::Skip => return Ok(true),
+        Plan::New => None,
+        Plan::Again(meta) => Some(meta),
+    };
 
     if let Some(parent) = meta_path.parent() {
         fs::create_dir_all(parent)?;
     }
 
-    let igdb = igdb_client.get_metadata(title, collection.platform)?;
+    if let Some(playlist) = &playlist {
+        disc::write_playlist(&collection.path, playlist, &discs)?;
+    }
+
+    let (hashes, header) = if is_folder {
+        (None, folder_header)
+    } else {
+        let hashes = hash::from_rom(&rom_path)?;
+        (Some(hashes), read_header(collection, &rom_path))
+    };
+
+    let serial = header.as_ref().and_then(|h| h.code.as_deref());
+    let dat = identify(collection, rom, hashes.as_ref(), serial, system)?;
+
+    // DAT names carry the most reliable tags
+    let name = match &dat {
+        Some(entry) => entry.name.clone(),
+        None => rom.file_stem().unwrap_or_default().to_string(),
+    };
+    let parsed_title = normalizer.parse(&name);
+
+    let header_title = header.as_ref().and_then(|h| h.title.as_deref());
+    let title = match &dat {
+        Some(_) => parsed_title.title.clone(),
+        None => normalizer.resolve(rom, header_title),
+    };
+
+    // e.g. from the serial, as SLUS discs are from the USA
+    let region = header.as_ref().and_then(|h| h.region.as_deref());
+
+    let pin = pin(collection, rom, options, previous.as_deref());
+    let (game, pin) = match (&dat, collection.provider, pin) {
+        (_, provider::Kind::Igdb, Some(id)) => {
+            let game = system.get::<igdb::Client>()?.get_game_by_id(id)?;
+            (game.into(), Some(id.to_string()))
+        }
+        (_, _, Some(_)) => return Err(anyhow!("pinned ids are only supported by igdb")),
+        (Some(entry), provider::Kind::Dat, None) => (entry.to_game(&title), None),
+        (_, provider::Kind::Igdb, None) if options.interactive => {
+            match choose_game(&title, collection.platform, region, system)? {
+                Some(chosen) => chosen,
+                None => return Ok(true),
+            }
+        }
+        (_, kind, None) => {
+            let provider = provider::get(system, kind)?;
+            (
+                provider.get_metadata(&title, collection.platform, region)?,
+                None,
+            )
+        }
+    };
+
     let meta = Meta {
-        file: rom.into(),
-        igdb,
+        file: playlist.unwrap_or_else(|| rom.into()),
+        provider: collection.provider,
+        game,
+        hashes,
+        dat,
+        discs: set.map(|_| discs),
+        header,
+        title: Some(parsed_title),
+        pin,
     };
 
+    if let Some(previous) = previous {
+        let changed = previous.game.changed_fields(&meta.game);
+        info!(
+            msg = "refreshed",
+            rom = rom.to_string(),
+            changed = changed.join(", ")
+        );
+        remove_stale_images(&meta_path, &previous.game, &meta.game)?;
+    }
+
     let meta_str = to_yaml_str(&meta)?;
     fs::write(meta_path, meta_str)?;
 
     Ok(false)
 }
 
+// so that they're downloaded again
+fn remove_stale_images(
+    meta_path: &Path,
+    previous: &provider::Game,
+    game: &provider::Game,
+) -> Result<()> {
+    for kind in ImageKind::ALL {
+        let image_path = meta::image_path(meta_path, kind);
+        if previous.image(kind) != game.image(kind) && image_path.exists() {
+            fs::remove_file(image_path)?;
+        }
+    }
+    Ok(())
+}
+
+// e.g. 30d, 12h, 2w
+fn parse_duration(text: &str) -> Result<Duration> {
+    let unit_len = text.chars().last().map_or(0, char::len_utf8);
+    let (number, unit) = text.split_at(text.len() - unit_len);
+    let number: u64 = number
+        .parse()
+        .with_context(|| format!("invalid duration {}", text))?;
+    let secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(anyhow!("invalid duration unit in {}", text)),
+    };
+    Ok(Duration::from_secs(number * secs))
+}
+
+// only * and ? are special
+fn glob_regex(pattern: &str) -> Result<Regex> {
+    let regex = regex::escape(pattern)
+        .replace(r"\*", ".*")
+        .replace(r"\?", ".");
+    Regex::new(&format!("(?i)^{}$", regex)).map_err(|e| e.into())
+}
+
+// asks the user if there's no clear match, in which case the choice is pinned
+// none if the user skips the rom
+fn choose_game(
+    title: &str,
+    platform: Platform,
+    region: Option<&str>,
+    system: &System,
+) -> Result<Option<(provider::Game, Option<String>)>> {
+    let igdb = system.get::<igdb::Client>()?;
+    let min_confidence = system.config.yaml.igdb.min_confidence;
+
+    let mut query = title.to_owned();
+    let mut results = igdb.search(&query, platform, region)?;
+
+    if !is_ambiguous(&results, min_confidence) {
+        let (game, m) = results.remove(0);
+        return Ok(Some((game.into_matched(m), None)));
+    }
+
+    loop {
+        let options: Vec<String> = results
+            .iter()
+            .map(|(game, m)| format!("{} ({:.2})", game.describe(), m.confidence))
+            .collect();
+
+        match prompt::choose(&query, &options)? {
+            prompt::Choice::Pick(i) => {
+                let (game, m) = results.swap_remove(i);
+                let pin = game.id.to_string();
+                return Ok(Some((game.into_matched(m), Some(pin))));
+            }
+            prompt::Choice::Search(new_query) => {
+                results = igdb.search(&new_query, platform, region)?;
+                query = new_query;
+            }
+            prompt::Choice::Skip => return Ok(None),
+        }
+    }
+}
+
+fn is_ambiguous(results: &[(igdb::Game, provider::Match)], min_confidence: f32) -> bool {
+    match results {
+        [] => true,
+        [(_, best), ..] if best.confidence < min_confidence => true,
+        [(_, best), (_, second), ..] => best.confidence - second.confidence < AMBIGUITY_MARGIN,
+        _ => false,
+    }
+}
+
+fn identify(
+    collection: &Collection,
+    rom: &Path,
+    hashes: Option<&hash::Hashes>,
+    serial: Option<&str>,
+    system: &System,
+) -> Result<Option<dat::Entry>> {
+    if collection.dats.is_none() {
+        return Ok(None);
+    }
+
+    let store = system.get::<dat::Store>()?;
+
+    // serials are shared by revisions, so hashes come first
+    let entry = hashes
+        .and_then(|h| store.find(collection.platform, h))
+        .or_else(|| serial.and_then(|s| store.find_by_serial(collection.platform, s)))
+        .cloned();
+
+    if entry.is_none() {
+        info!(
+            msg = "no dat entry",
+            rom = rom.to_string(),
+            crc32 = hashes.map(|h| h.crc32.as_str()).unwrap_or_default(),
+            serial = serial.unwrap_or_default()
+        );
+    }
+
+    Ok(entry)
+}
+
+fn read_header(collection: &Collection, rom_path: &Path) -> Option<header::Header> {
+    match header::read(rom_path, collection.platform) {
+        Ok(header) => header,
+        Err(e) => {
+            debug!(
+                msg = "unable to read header",
+                rom = rom_path.to_string(),
+                error = e.to_string()
+            );
+            None
+        }
+    }
+}
+
 impl Runnable for Input {
     fn run(&self, system: System) -> Result<()> {
+        if self.refresh {
+            system.get::<Cache>()?.bypass_reads();
+        }
+
+        // searches that weren't prefetched are sent one by one instead
+        if let Err(e) = self.prefetch(&system) {
+            error!(msg = "unable to prefetch searches", error = e.to_string());
+        }
         worker::run(self, system)
     }
 }
@@ -110,6 +600,29 @@ impl Runnable for Input {
 impl HasDeps for Input {
     fn deps(&self) -> HashSet<TypeId> {
         use crate::components::*;
-        [*TWITCH, *IGDB].into()
+        [*CACHE, *TWITCH, *IGDB, *TGDB, *DAT].into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30d").unwrap(), Duration::from_secs(2592000));
+        assert_eq!(parse_duration("12h").unwrap(), Duration::from_secs(43200));
+        assert!(parse_duration("12").is_err());
+        assert!(parse_duration("d").is_err());
+    }
+
+    #[test]
+    fn test_glob_regex() {
+        let regex = glob_regex("pokemon*.zip").unwrap();
+        assert!(regex.is_match("Pokemon FireRed.zip"));
+        assert!(!regex.is_match("Pokemon FireRed.gba"));
+        assert!(glob_regex("Tetris (?).gb")
+            .unwrap()
+            .is_match("Tetris (W).gb"));
     }
 }
@@ -1,20 +1,73 @@
+use crate::cache::Cache;
+use crate::dat;
 use crate::igdb;
 use crate::prelude::*;
+use crate::provider;
+use crate::tgdb;
 use crate::twitch;
 use once_cell::sync::Lazy;
 
+pub static CACHE: Lazy<TypeId> = Lazy::new(TypeId::of::<Cache>);
 pub static TWITCH: Lazy<TypeId> = Lazy::new(TypeId::of::<twitch::Client>);
 pub static IGDB: Lazy<TypeId> = Lazy::new(TypeId::of::<igdb::Client>);
+pub static TGDB: Lazy<TypeId> = Lazy::new(TypeId::of::<tgdb::Client>);
+pub static DAT: Lazy<TypeId> = Lazy::new(TypeId::of::<dat::Store>);
 
 static DEP_ERROR: &str = "dependency not initialized";
 
 pub fn init(system: &mut System) -> Result<()> {
-    let twitch = system.maybe_add(&*TWITCH, |s| twitch::Client::new(&s.config))?;
+    let uses = |kind: provider::Kind| {
+        system
+            .config
+            .yaml
+            .collections
+            .iter()
+            .any(|c| c.provider == kind)
+    };
+    let uses_igdb = uses(provider::Kind::Igdb);
+    let uses_tgdb = uses(provider::Kind::Tgdb);
+    let uses_dat = uses(provider::Kind::Dat)
+        || system
+            .config
+            .yaml
+            .collections
+            .iter()
+            .any(|c| c.dats.is_some());
 
-    let _igdb = system.maybe_add(&*IGDB, |s| {
-        let twitch = twitch.context(DEP_ERROR)?;
-        igdb::Client::new(Arc::clone(&s.config), &twitch)
-    })?;
+    // shared by the clients, so that it's pruned once
+    let cache = system.maybe_add(&*CACHE, |s| Cache::new(&s.config))?;
+
+    if uses_igdb {
+        let offline = system.config.cli.offline;
+
+        // twitch refreshes its token on init
+        let twitch = if offline {
+            None
+        } else {
+            system.maybe_add(&*TWITCH, |s| twitch::Client::new(&s.config))?
+        };
+
+        let _igdb = system.maybe_add(&*IGDB, |s| {
+            let twitch = if offline {
+                None
+            } else {
+                Some(twitch.context(DEP_ERROR)?)
+            };
+            let cache = cache.clone().context(DEP_ERROR)?;
+            igdb::Client::new(Arc::clone(&s.config), twitch.as_deref(), cache)
+        })?;
+    }
+
+    if uses_tgdb {
+        let _tgdb = system.maybe_add(&*TGDB, |s| {
+            let cache = cache.context(DEP_ERROR)?;
+            tgdb::Client::new(Arc::clone(&s.config), cache)
+        })?;
+    }
+
+    if uses_dat {
+        let _dat = system.maybe_add(&*DAT, |s| dat::Store::new(&s.config))?;
+    }
 
     Ok(())
 }
@@ -8,6 +8,12 @@ pub enum Cmd {
     Scrape(commands::scrape::Input),
     Media(commands::media::Input),
     Pegasus(commands::pegasus::Input),
+    Es(commands::es::Input),
+    Retroarch(commands::retroarch::Input),
+    Launchbox(commands::launchbox::Input),
+    Attract(commands::attract::Input),
+    Match(commands::pin::Input),
+    Report(commands::report::Input),
     Config(commands::config::Input),
 }
 
@@ -20,6 +26,14 @@ pub struct ClapConfig {
 
     #[clap(short, long)]
     pub config: Option<PathBuf>,
+
+    /// Ignore cached API responses
+    #[clap(long, global = true)]
+    pub no_cache: bool,
+
+    /// Only use cached API responses and DATs
+    #[clap(long, global = true)]
+    pub offline: bool,
 }
 
 impl ClapConfig {
@@ -4,6 +4,8 @@ use crate::collection::Collection;
 use crate::config;
 use crate::platform::Platform;
 use crate::prelude::*;
+use crate::provider;
+use crate::title;
 use dns_common::deser::yaml_from_path as from_path;
 
 static YAML_FILE_NAME: &str = "config.yaml";
@@ -20,6 +22,53 @@ pub struct Igdb {
     pub api_base_url: String,
     pub images_base_url: String,
     pub denylisted_name_substrings: HashSet<String>,
+    #[serde(default = "default_min_confidence")]
+    pub min_confidence: f32,
+    #[serde(default = "default_requests_per_second")]
+    pub requests_per_second: f32,
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tgdb {
+    pub api_key: String,
+    pub api_base_url: String,
+    pub images_base_url: String,
+    #[serde(default)]
+    pub denylisted_name_substrings: HashSet<String>,
+    #[serde(default = "default_min_confidence")]
+    pub min_confidence: f32,
+}
+
+fn default_min_confidence() -> f32 {
+    0.7
+}
+
+// IGDB's limit
+fn default_requests_per_second() -> f32 {
+    4.0
+}
+
+fn default_max_retries() -> u32 {
+    5
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Cache {
+    pub ttl_days: u32,
+    pub max_size_mb: u32,
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self {
+            ttl_days: 30,
+            max_size_mb: 100,
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize)]
@@ -35,7 +84,12 @@ pub struct Twitch {
 pub struct YamlConfig {
     pub parallelism: Parallelism,
     pub igdb: Igdb,
+    pub tgdb: Option<Tgdb>,
     pub twitch: Twitch,
+    #[serde(default)]
+    pub cache: Cache,
+    #[serde(default = "title::default_rules")]
+    pub title_rules: Vec<title::Rule>,
     pub collections: Vec<Collection>,
     pub tracing: Option<TracingConfig>,
 }
@@ -98,16 +152,34 @@ impl Default for YamlConfig {
                 api_base_url: "https://api.igdb.com".into(),
                 images_base_url: "https://images.igdb.com".into(),
                 denylisted_name_substrings: HashSet::new(),
+                min_confidence: default_min_confidence(),
+                requests_per_second: default_requests_per_second(),
+                max_retries: default_max_retries(),
             },
+            tgdb: Some(Tgdb {
+                api_key: "__API_KEY__".into(),
+                api_base_url: "https://api.thegamesdb.net".into(),
+                images_base_url: "https://cdn.thegamesdb.net/images".into(),
+                denylisted_name_substrings: HashSet::new(),
+                min_confidence: default_min_confidence(),
+            }),
+            cache: Cache::default(),
+            title_rules: title::default_rules(),
             collections: vec![
                 Collection {
                     name: "Game Boy Advance".into(),
                     path: PathBuf::from("/path/to/ROMs/gba"),
                     extensions: vec!["zip".into()],
                     platform: Platform::GameBoyAdvance,
+                    provider: provider::Kind::Igdb,
                     launch: "m start -n com.retroarch.aarch64/com.retroarch.browser.retroactivity.RetroActivityFuture -e ROM {file.path} -e LIBRETRO /data/data/com.retroarch.aarch64/cores/mgba_libretro_android.so -e CONFIGFILE /storage/emulated/0/Android/data/com.retroarch.aarch64/files/retroarch.cfg -e QUITFOCUS --activity-clear-task --activity-clear-top --activity-no-history".into(),
                     denylist: None,
+                    dats: None,
+                    m3u: false,
+                    title_rules: None,
                     title_map: None,
+                    id_map: None,
+                    retroarch_core: None,
                 },
             ],
             tracing: Some(TracingConfig {
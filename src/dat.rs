@@ -0,0 +1,247 @@
+use crate::hash::Hashes;
+use crate::platform::Platform;
+use crate::prelude::*;
+use crate::provider::{self, ImageKind, MetadataProvider};
+use crate::title;
+use std::fs;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Entry {
+    pub name: String,
+    pub rom: String,
+    pub region: Option<String>,
+    #[serde(default)]
+    pub languages: Vec<String>,
+    pub serial: Option<String>,
+}
+
+impl Entry {
+    pub fn to_game(&self, title: &str) -> provider::Game {
+        provider::Game {
+            id: self.serial.clone().unwrap_or_else(|| self.name.clone()),
+            name: title.to_owned(),
+            confidence: Some(1.0),
+            ..Default::default()
+        }
+    }
+}
+
+// DATs by platform, so that games with the same name on other platforms never match
+#[derive(Default)]
+pub struct Store {
+    dats: HashMap<Platform, Dat>,
+}
+
+#[derive(Default)]
+struct Dat {
+    entries: Vec<Entry>,
+    by_hash: HashMap<String, usize>,
+    by_serial: HashMap<String, usize>,
+    by_title: HashMap<String, usize>,
+}
+
+impl Store {
+    pub fn new(config: &Config) -> Result<Self> {
+        trace!("init");
+
+        let mut store = Self::default();
+        let mut loaded = HashSet::new();
+        let normalizer = title::Normalizer::new(&config.yaml.title_rules);
+
+        for collection in &config.yaml.collections {
+            for path in collection.dats.iter().flatten() {
+                if !loaded.insert((collection.platform, path.clone())) {
+                    continue;
+                }
+
+                let xml = fs::read_to_string(path)
+                    .with_context(|| format!("unable to read dat {}", path.to_string()))?;
+                let dat = store.dats.entry(collection.platform).or_default();
+                let n = dat.add(&xml, &normalizer)?;
+                info!(msg = "dat loaded", path = path.to_string(), entries = n);
+            }
+        }
+
+        Ok(store)
+    }
+
+    pub fn find(&self, platform: Platform, hashes: &Hashes) -> Option<&Entry> {
+        self.dats.get(&platform)?.find(hashes)
+    }
+
+    pub fn find_by_serial(&self, platform: Platform, serial: &str) -> Option<&Entry> {
+        self.dats.get(&platform)?.find_by_serial(serial)
+    }
+
+    pub fn find_by_title(&self, platform: Platform, title: &str) -> Option<&Entry> {
+        self.dats.get(&platform)?.find_by_title(title)
+    }
+}
+
+impl Dat {
+    fn add(&mut self, xml: &str, normalizer: &title::Normalizer) -> Result<usize> {
+        let doc = roxmltree::Document::parse(xml)?;
+        let mut n = 0;
+
+        let games = doc
+            .descendants()
+            .filter(|node| node.has_tag_name("game") || node.has_tag_name("machine"));
+
+        for game in games {
+            let name = match game.attribute("name") {
+                Some(name) => name,
+                None => continue,
+            };
+
+            let game_serial = game
+                .children()
+                .find(|node| node.has_tag_name("serial"))
+                .and_then(|node| node.text());
+
+            let parsed = normalizer.parse(name);
+
+            for rom in game.children().filter(|node| node.has_tag_name("rom")) {
+                let index = self.entries.len();
+
+                let serial = rom
+                    .attribute("serial")
+                    .or(game_serial)
+                    .filter(|s| !s.is_empty());
+
+                self.entries.push(Entry {
+                    name: name.to_owned(),
+                    rom: rom.attribute("name").unwrap_or(name).to_owned(),
+                    region: Some(parsed.regions.join(", ")).filter(|r| !r.is_empty()),
+                    languages: parsed.languages.clone(),
+                    serial: serial.map(|s| s.to_owned()),
+                });
+
+                for attr in ["crc", "md5", "sha1"] {
+                    if let Some(hash) = rom.attribute(attr) {
+                        self.by_hash.insert(hash.to_ascii_lowercase(), index);
+                    }
+                }
+
+                // e.g. "SLUS-00561, SLUS-00562"
+                for s in serial.iter().flat_map(|s| s.split(',')) {
+                    self.by_serial.entry(normalize_serial(s)).or_insert(index);
+                }
+
+                let title = parsed.title.to_lowercase();
+                self.by_title.entry(title).or_insert(index);
+
+                n += 1;
+            }
+        }
+
+        Ok(n)
+    }
+
+    fn find(&self, hashes: &Hashes) -> Option<&Entry> {
+        [&hashes.sha1, &hashes.md5, &hashes.crc32]
+            .iter()
+            .find_map(|hash| self.by_hash.get(*hash))
+            .and_then(|&i| self.entries.get(i))
+    }
+
+    fn find_by_serial(&self, serial: &str) -> Option<&Entry> {
+        self.by_serial
+            .get(&normalize_serial(serial))
+            .and_then(|&i| self.entries.get(i))
+    }
+
+    fn find_by_title(&self, title: &str) -> Option<&Entry> {
+        self.by_title
+            .get(&title.to_lowercase())
+            .and_then(|&i| self.entries.get(i))
+    }
+}
+
+// SLUS-00561, SLUS_005.61 and slus00561 are the same serial
+fn normalize_serial(serial: &str) -> String {
+    serial
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+impl MetadataProvider for Store {
+    fn get_metadata(
+        &self,
+        name: &str,
+        platform: Platform,
+        _region: Option<&str>,
+    ) -> Result<provider::Game> {
+        self.find_by_title(platform, name)
+            .map(|entry| entry.to_game(name))
+            .with_context(|| format!("no dat entry for {}", name))
+    }
+
+    fn download(&self, _image: &provider::Image, _kind: ImageKind, _path: &Path) -> Result<()> {
+        Err(anyhow!("dat files have no images"))
+    }
+}
+
+impl Component for Store {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        let xml = r#"<?xml version="1.0"?>
+<!DOCTYPE datafile PUBLIC "-//Logiqx//DTD ROM Management Datafile//EN" "http://www.logiqx.com/Dats/datafile.dtd">
+<datafile>
+	<header>
+		<name>Nintendo - Game Boy Advance</name>
+	</header>
+	<game name="Legend of Zelda, The - The Minish Cap (Europe) (En,Fr,De,Es,It)">
+		<description>Legend of Zelda, The - The Minish Cap (Europe) (En,Fr,De,Es,It)</description>
+		<rom name="Legend of Zelda, The - The Minish Cap (Europe) (En,Fr,De,Es,It).gba" size="16777216" crc="2AF6B8F1" md5="a2ff0a3a9d5bb3d1f9e6a1b1e4a9e3b3" sha1="cc4a1d7d1c1c0c3ac8b1b42f85d22f6b91ca94c4" serial="BZMP"/>
+	</game>
+	<game name="Pokemon - Emerald Version (USA, Europe)">
+		<description>Pokemon - Emerald Version (USA, Europe)</description>
+		<rom name="Pokemon - Emerald Version (USA, Europe).gba" size="16777216" crc="1F1C08FB" md5="605b89b67018abcea91e693a4dd25be3" sha1="f3ae088181bf583e55daf962a92bb46f4f1d07b7"/>
+	</game>
+</datafile>"#;
+
+        let rules = title::default_rules();
+        let normalizer = title::Normalizer::new(&rules);
+
+        let mut dat = Dat::default();
+        assert_eq!(dat.add(xml, &normalizer).unwrap(), 2);
+        let mut store = Store::default();
+        store.dats.insert(Platform::GameBoyAdvance, dat);
+        let gba = Platform::GameBoyAdvance;
+
+        let hashes = Hashes {
+            crc32: "1f1c08fb".into(),
+            md5: "".into(),
+            sha1: "".into(),
+        };
+        let entry = store.find(gba, &hashes).unwrap();
+        assert_eq!(entry.name, "Pokemon - Emerald Version (USA, Europe)");
+        assert_eq!(entry.region.as_deref(), Some("USA, Europe"));
+        assert!(entry.languages.is_empty());
+        assert_eq!(entry.serial, None);
+
+        let entry = store
+            .find_by_title(gba, "The Legend of Zelda - The Minish Cap")
+            .unwrap();
+        assert_eq!(entry.region.as_deref(), Some("Europe"));
+        assert_eq!(entry.languages, ["En", "Fr", "De", "Es", "It"]);
+        assert_eq!(entry.serial.as_deref(), Some("BZMP"));
+
+        let entry = store.find_by_serial(gba, "bzmp").unwrap();
+        assert_eq!(
+            entry.rom,
+            "Legend of Zelda, The - The Minish Cap (Europe) (En,Fr,De,Es,It).gba"
+        );
+
+        // the same game, looked up for another platform
+        assert!(store.find(Platform::GameBoy, &hashes).is_none());
+        assert!(store.find_by_serial(Platform::GameBoy, "bzmp").is_none());
+    }
+}
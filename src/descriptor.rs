@@ -0,0 +1,124 @@
+use crate::prelude::*;
+use std::fmt;
+use std::fs;
+
+pub static EXTENSIONS: [&str; 3] = ["cue", "gdi", "ccd"];
+
+#[derive(Debug)]
+pub struct MissingTracks(pub Vec<PathBuf>);
+
+impl fmt::Display for MissingTracks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tracks: Vec<String> = self.0.iter().map(|t| t.to_string()).collect();
+        write!(f, "missing tracks: {}", tracks.join(", "))
+    }
+}
+
+impl std::error::Error for MissingTracks {}
+
+pub fn is_descriptor(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .unwrap_or_default()
+        .to_string()
+        .to_ascii_lowercase();
+    EXTENSIONS.contains(&extension.as_str())
+}
+
+// track files referenced by a .cue, .gdi or .ccd, next to it
+pub fn tracks(path: &Path) -> Result<Vec<PathBuf>> {
+    let extension = path
+        .extension()
+        .unwrap_or_default()
+        .to_string()
+        .to_ascii_lowercase();
+
+    let names = match extension.as_str() {
+        "cue" => cue_tracks(&fs::read_to_string(path)?),
+        "gdi" => gdi_tracks(&fs::read_to_string(path)?),
+        // CloneCD images are named after the descriptor
+        "ccd" => vec![path
+            .with_extension("img")
+            .file_name()
+            .unwrap_or_default()
+            .to_string()],
+        _ => return Err(anyhow!("not a descriptor")),
+    };
+
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    Ok(names.into_iter().map(|n| dir.join(n)).collect())
+}
+
+pub fn missing_tracks(path: &Path) -> Result<Vec<PathBuf>> {
+    let tracks = tracks(path)?;
+    if tracks.is_empty() {
+        return Err(anyhow!("no tracks in {}", path.to_string()));
+    }
+    Ok(tracks.into_iter().filter(|t| !t.exists()).collect())
+}
+
+// FILE "Game (Track 1).bin" BINARY
+fn cue_tracks(cue: &str) -> Vec<String> {
+    cue.lines()
+        .map(|l| l.trim())
+        .filter(|l| l.starts_with("FILE "))
+        .filter_map(|l| {
+            let rest = l.trim_start_matches("FILE ").trim();
+            if rest.starts_with('"') {
+                rest.split('"').nth(1)
+            } else {
+                rest.split_whitespace().next()
+            }
+        })
+        .map(|f| f.to_owned())
+        .collect()
+}
+
+// 2 600 0 2352 "track 02.raw" 0
+fn gdi_tracks(gdi: &str) -> Vec<String> {
+    gdi.lines()
+        .skip(1)
+        .filter_map(|l| {
+            let mut rest = l.trim();
+            for _ in 0..4 {
+                let (_, r) = rest.split_once(char::is_whitespace)?;
+                rest = r.trim_start();
+            }
+            if rest.starts_with('"') {
+                rest.split('"').nth(1)
+            } else {
+                rest.split_whitespace().next()
+            }
+        })
+        .map(|f| f.to_owned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cue_tracks() {
+        let cue = r#"FILE "Final Fantasy VII (USA) (Disc 1).bin" BINARY
+  TRACK 01 MODE2/2352
+    INDEX 01 00:00:00
+FILE track02.bin BINARY
+  TRACK 02 AUDIO
+    INDEX 00 00:00:00
+"#;
+        assert_eq!(
+            cue_tracks(cue),
+            ["Final Fantasy VII (USA) (Disc 1).bin", "track02.bin"]
+        );
+    }
+
+    #[test]
+    fn test_gdi_tracks() {
+        let gdi = "3\r\n1 0 4 2352 track01.bin 0\r\n2 600 0 2352 \"track 02.raw\" 0\r\n3 45000 4 2352 track03.bin 0\r\n";
+        assert_eq!(
+            gdi_tracks(gdi),
+            ["track01.bin", "track 02.raw", "track03.bin"]
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use crate::prelude::*;
+use std::fs;
+
+static DISC: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\s*\((?:disc|disk|cd)\s*(\d+)(?:\s*of\s*\d+)?\)").expect("invalid regex")
+});
+
+pub static PLAYLIST_EXTENSION: &str = "m3u";
+
+// e.g. 2 for "Final Fantasy VII (USA) (Disc 2).cue"
+pub fn number(path: &Path) -> Option<u32> {
+    let filename = path.file_name()?.to_string_lossy();
+    DISC.captures(&filename)?.get(1)?.as_str().parse().ok()
+}
+
+// e.g. "Final Fantasy VII (USA).cue" for all of its discs
+pub fn set_path(path: &Path) -> Option<PathBuf> {
+    let filename = path.file_name()?.to_string_lossy();
+    if !DISC.is_match(&filename) {
+        return None;
+    }
+    let filename = DISC.replace(&filename, "").to_string();
+    Some(path.with_file_name(filename))
+}
+
+pub fn playlist_path(set_path: &Path) -> PathBuf {
+    set_path.with_extension(PLAYLIST_EXTENSION)
+}
+
+pub fn is_playlist(path: &Path) -> bool {
+    path.extension()
+        .unwrap_or_default()
+        .to_string()
+        .eq_ignore_ascii_case(PLAYLIST_EXTENSION)
+}
+
+// keeps only the first disc of each set, since it stands for the whole set
+pub fn group(filepaths: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut firsts: HashMap<PathBuf, (u32, PathBuf)> = HashMap::new();
+    let mut others = vec![];
+
+    for path in filepaths {
+        match (set_path(&path), number(&path)) {
+            (Some(set), Some(n)) => {
+                let is_first = firsts.get(&set).map_or(true, |(m, _)| n < *m);
+                if is_first {
+                    firsts.insert(set, (n, path));
+                }
+            }
+            _ => others.push(path),
+        }
+    }
+
+    others.extend(firsts.into_values().map(|(_, path)| path));
+    others.sort();
+    others
+}
+
+// all discs in the same set as `rom`, in order, relative to the collection
+pub fn siblings(collection_path: &Path, rom: &Path) -> Result<Vec<PathBuf>> {
+    let set = match set_path(rom) {
+        Some(set) => set,
+        None => return Ok(vec![]),
+    };
+
+    let dir = rom.parent().unwrap_or_else(|| Path::new(""));
+    let extension = rom.extension().unwrap_or_default();
+
+    let mut discs: Vec<(u32, PathBuf)> = fs::read_dir(collection_path.join(dir))?
+        .filter_map(|e| e.ok())
+        .map(|e| dir.join(e.file_name()))
+        .filter(|p| p.extension().unwrap_or_default() == extension)
+        .filter(|p| set_path(p).as_ref() == Some(&set))
+        .filter_map(|p| number(&p).map(|n| (n, p)))
+        .collect();
+
+    discs.sort();
+    Ok(discs.into_iter().map(|(_, p)| p).collect())
+}
+
+pub fn write_playlist(collection_path: &Path, playlist: &Path, discs: &[PathBuf]) -> Result<()> {
+    let lines: Vec<String> = discs
+        .iter()
+        .filter_map(|d| d.file_name())
+        .map(|f| f.to_string())
+        .collect();
+
+    let content = format!("{}\n", lines.join("\n"));
+    fs::write(collection_path.join(playlist), content)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group() {
+        let filepaths = [
+            "Final Fantasy VII (USA) (Disc 2).cue",
+            "Final Fantasy VII (USA) (Disc 1).cue",
+            "Final Fantasy VII (USA) (Disc 3).cue",
+            "Metal Gear Solid (USA) (Disc 1) (Rev 1).cue",
+            "Metal Gear Solid (USA) (Disc 2) (Rev 1).cue",
+            "Mega Man X4 (USA).cue",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+
+        assert_eq!(
+            group(filepaths),
+            [
+                "Final Fantasy VII (USA) (Disc 1).cue",
+                "Mega Man X4 (USA).cue",
+                "Metal Gear Solid (USA) (Disc 1) (Rev 1).cue",
+            ]
+            .iter()
+            .map(PathBuf::from)
+            .collect::<Vec<_>>()
+        );
+
+        let disc = Path::new("Metal Gear Solid (USA) (Disc 2) (Rev 1).cue");
+        assert_eq!(number(disc), Some(2));
+        assert_eq!(
+            set_path(disc),
+            Some(PathBuf::from("Metal Gear Solid (USA) (Rev 1).cue"))
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use crate::collection::Collection;
+use crate::meta::{self, Meta};
+use crate::prelude::*;
+use crate::provider::ImageKind;
+use crate::xml::escape;
+use chrono::prelude::*;
+use std::fmt::Write as _;
+
+// fields louvre fills in; the others, such as playcount and lastplayed, belong to ES
+static OWNED_FIELDS: [&str; 12] = [
+    "path",
+    "name",
+    "desc",
+    "image",
+    "thumbnail",
+    "fanart",
+    "releasedate",
+    "developer",
+    "publisher",
+    "genre",
+    "players",
+    "rating",
+];
+
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+    pub tag: String,                       // game or folder
+    pub attributes: Vec<(String, String)>, // e.g. the id and source set by ES-DE
+    pub path: String,
+    pub fields: Vec<(String, String)>,
+}
+
+pub fn from_meta(meta: Meta, meta_file: &Path, collection: &Collection) -> Result<Entry> {
+    let game = meta.game;
+    let mut fields = vec![];
+    let mut add = |name: &str, value: String| fields.push((name.to_owned(), value));
+
+    add("name", game.name);
+
+    if let Some(x) = game.summary.or(game.description) {
+        add("desc", x);
+    }
+
+    // a marquee is a logo, which providers don't have; fanart is read by ES-DE and Batocera
+    let images = [
+        ("image", ImageKind::Screenshot),
+        ("thumbnail", ImageKind::BoxFront),
+        ("fanart", ImageKind::Background),
+    ];
+    for (name, kind) in images {
+        let image_path = meta::image_path(meta_file, kind);
+        if let Ok(relative) = image_path.strip_prefix(&collection.path) {
+            if image_path.exists() {
+                add(name, relative_path(relative));
+            }
+        }
+    }
+
+    if let Some(secs) = game.release_date {
+        let naive = NaiveDateTime::from_timestamp_opt(secs, 0).context("invalid date")?;
+        add("releasedate", naive.format("%Y%m%dT%H%M%S").to_string());
+    }
+
+    if !game.developers.is_empty() {
+        add("developer", game.developers.join(", "));
+    }
+
+    if !game.publishers.is_empty() {
+        add("publisher", game.publishers.join(", "));
+    }
+
+    if !game.genres.is_empty() {
+        add("genre", game.genres.join(", "));
+    }
+
+    if let Some(x) = game.players {
+        add("players", x.to_string());
+    }
+
+    if let Some(x) = game.rating {
+        add("rating", format!("{:.2}", (x / 100.0).clamp(0.0, 1.0)));
+    }
+
+    Ok(Entry {
+        tag: "game".to_owned(),
+        attributes: vec![],
+        path: relative_path(&meta.file),
+        fields,
+    })
+}
+
+// ES paths are relative to the system folder
+fn relative_path(path: &Path) -> String {
+    format!("./{}", path.to_string())
+}
+
+pub fn parse(xml: &str) -> Result<Vec<Entry>> {
+    let doc = roxmltree::Document::parse(xml)?;
+
+    let entries = doc
+        .root_element()
+        .children()
+        .filter(|node| node.has_tag_name("game") || node.has_tag_name("folder"))
+        .filter_map(|node| {
+            let fields: Vec<(String, String)> = node
+                .children()
+                .filter(|child| child.is_element())
+                .map(|child| {
+                    let name = child.tag_name().name().to_owned();
+                    (name, child.text().unwrap_or_default().to_owned())
+                })
+                .collect();
+            let path = fields.iter().find(|(name, _)| name == "path")?.1.clone();
+            Some(Entry {
+                tag: node.tag_name().name().to_owned(),
+                attributes: node
+                    .attributes()
+                    .iter()
+                    .map(|a| (a.name().to_owned(), a.value().to_owned()))
+                    .collect(),
+                path,
+                fields: fields
+                    .into_iter()
+                    .filter(|(name, _)| name != "path")
+                    .collect(),
+            })
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+// keeps the ES fields of previous entries, and the entries louvre knows nothing about
+// as long as their roms still exist
+pub fn merge(entries: Vec<Entry>, previous: Vec<Entry>, collection_path: &Path) -> Vec<Entry> {
+    let mut previous_by_path: HashMap<String, Entry> = previous
+        .into_iter()
+        .map(|entry| (entry.path.clone(), entry))
+        .collect();
+
+    let mut merged: Vec<Entry> = entries
+        .into_iter()
+        .map(|mut entry| {
+            if let Some(old) = previous_by_path.remove(&entry.path) {
+                let es_fields = old
+                    .fields
+                    .into_iter()
+                    .filter(|(name, _)| !OWNED_FIELDS.contains(&name.as_str()));
+                entry.fields.extend(es_fields);
+                entry.attributes = old.attributes;
+            }
+            entry
+        })
+        .collect();
+
+    let mut rest: Vec<Entry> = previous_by_path
+        .into_values()
+        .filter(|entry| absolute_path(&entry.path, collection_path).exists())
+        .collect();
+    rest.sort_by(|a, b| a.path.cmp(&b.path));
+    merged.append(&mut rest);
+
+    merged
+}
+
+fn absolute_path(path: &str, collection_path: &Path) -> PathBuf {
+    collection_path.join(path.trim_start_matches("./"))
+}
+
+pub fn to_str(entries: &[Entry]) -> Result<String> {
+    let mut buf = String::new();
+
+    writeln!(buf, "<?xml version=\"1.0\"?>")?;
+    writeln!(buf, "<gameList>")?;
+    for entry in entries {
+        write!(buf, "\t<{}", entry.tag)?;
+        for (name, value) in &entry.attributes {
+            write!(buf, " {}=\"{}\"", name, escape(value))?;
+        }
+        writeln!(buf, ">")?;
+        writeln!(buf, "\t\t<path>{}</path>", escape(&entry.path))?;
+        for (name, value) in &entry.fields {
+            writeln!(buf, "\t\t<{}>{}</{}>", name, escape(value), name)?;
+        }
+        writeln!(buf, "\t</{}>", entry.tag)?;
+    }
+    writeln!(buf, "</gameList>")?;
+
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn entry(path: &str, fields: &[(&str, &str)]) -> Entry {
+        Entry {
+            tag: "game".to_owned(),
+            attributes: vec![],
+            path: path.to_owned(),
+            fields: fields
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    fn with_id(mut entry: Entry, id: &str) -> Entry {
+        entry.attributes = vec![("id".to_owned(), id.to_owned())];
+        entry
+    }
+
+    #[test]
+    fn test_merge() {
+        let collection_path = std::env::temp_dir().join("louvre-test_merge");
+        let _ = fs::remove_dir_all(&collection_path);
+        fs::create_dir_all(&collection_path).unwrap();
+        fs::write(collection_path.join("Custom.zip"), "").unwrap();
+
+        let previous = vec![
+            with_id(
+                entry(
+                    "./Doom.zip",
+                    &[
+                        ("name", "DOOM"),
+                        ("playcount", "3"),
+                        ("lastplayed", "20230101T000000"),
+                    ],
+                ),
+                "1234",
+            ),
+            entry("./Custom.zip", &[("name", "Custom")]),
+            entry("./Deleted.zip", &[("name", "Deleted")]),
+        ];
+        let entries = vec![entry("./Doom.zip", &[("name", "Doom")])];
+
+        assert_eq!(
+            merge(entries, previous, &collection_path),
+            vec![
+                with_id(
+                    entry(
+                        "./Doom.zip",
+                        &[
+                            ("name", "Doom"),
+                            ("playcount", "3"),
+                            ("lastplayed", "20230101T000000")
+                        ]
+                    ),
+                    "1234"
+                ),
+                entry("./Custom.zip", &[("name", "Custom")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_to_str() {
+        let entries = vec![with_id(
+            entry(
+                "./Tom & Jerry.zip",
+                &[("name", "Tom & Jerry"), ("playcount", "1")],
+            ),
+            "42",
+        )];
+        let xml = to_str(&entries).unwrap();
+        assert!(xml.contains("<game id=\"42\">"));
+        assert!(xml.contains("<path>./Tom &amp; Jerry.zip</path>"));
+        assert_eq!(parse(&xml).unwrap(), entries);
+    }
+}
@@ -0,0 +1,12 @@
+pub mod gamelist;
+
+use crate::collection::Collection;
+use crate::prelude::*;
+
+pub static XML_NAME: &str = "gamelist.xml";
+
+impl Collection {
+    pub fn es_path(&self) -> PathBuf {
+        self.path.join(XML_NAME)
+    }
+}
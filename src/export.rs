@@ -0,0 +1,32 @@
+use crate::prelude::*;
+use std::fs;
+
+// copies or links a file that frontends expect somewhere else
+pub fn place(original: &Path, target: &Path, symlink: bool) -> Result<()> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if target.symlink_metadata().is_ok() {
+        fs::remove_file(target)?;
+    }
+
+    if symlink {
+        link(original, target)
+    } else {
+        fs::copy(original, target)?;
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn link(original: &Path, target: &Path) -> Result<()> {
+    let original = original.canonicalize()?;
+    std::os::unix::fs::symlink(original, target)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn link(original: &Path, target: &Path) -> Result<()> {
+    fs::copy(original, target)?;
+    Ok(())
+}
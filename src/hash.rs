@@ -0,0 +1,101 @@
+use crate::archive;
+use crate::prelude::*;
+use crc32fast::Hasher as Crc32;
+use md5::{Digest, Md5};
+use sha1::Sha1;
+use std::io::{Read, Seek};
+use zip::ZipArchive;
+
+const BUFFER_SIZE: usize = 64 * 1024;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Hashes {
+    pub crc32: String,
+    pub md5: String,
+    pub sha1: String,
+}
+
+// hashes the ROM inside .zip files, so that they match DAT entries
+pub fn from_rom(path: &Path) -> Result<Hashes> {
+    if archive::is_zip(path) {
+        let res = File::open(path).map_err(|e| e.into()).and_then(from_zip);
+        match res {
+            Ok(hashes) => return Ok(hashes),
+            Err(e) => debug!(
+                msg = "unable to read zip, hashing it as a whole",
+                path = path.to_string(),
+                error = e.to_string()
+            ),
+        }
+    }
+
+    from_path(path)
+}
+
+fn from_zip<R: Read + Seek>(reader: R) -> Result<Hashes> {
+    let mut archive = ZipArchive::new(reader)?;
+    let i = archive::largest_entry(&mut archive)?;
+    let file = archive.by_index(i)?;
+    from_reader(file)
+}
+
+pub fn from_path(path: &Path) -> Result<Hashes> {
+    let file = File::open(path).with_context(|| format!("unable to open {}", path.to_string()))?;
+    from_reader(file)
+}
+
+pub fn from_reader<R: Read>(mut reader: R) -> Result<Hashes> {
+    let mut crc32 = Crc32::new();
+    let mut md5 = Md5::new();
+    let mut sha1 = Sha1::new();
+
+    let mut buffer = vec![0; BUFFER_SIZE];
+    loop {
+        let n = reader.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        crc32.update(&buffer[..n]);
+        md5.update(&buffer[..n]);
+        sha1.update(&buffer[..n]);
+    }
+
+    Ok(Hashes {
+        crc32: format!("{:08x}", crc32.finalize()),
+        md5: format!("{:x}", md5.finalize()),
+        sha1: format!("{:x}", sha1.finalize()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_reader() {
+        let hashes = from_reader("abc".as_bytes()).unwrap();
+        assert_eq!(hashes.crc32, "352441c2");
+        assert_eq!(hashes.md5, "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(hashes.sha1, "a9993e364706816aba3e25717850c26c9cd0d89d");
+    }
+
+    #[test]
+    fn test_from_zip() {
+        use std::io::{Cursor, Write};
+        use zip::write::{FileOptions, ZipWriter};
+
+        let mut writer = ZipWriter::new(Cursor::new(vec![]));
+        writer
+            .start_file("readme.txt", FileOptions::default())
+            .unwrap();
+        writer.write_all(b"a").unwrap();
+        writer
+            .start_file("game.gba", FileOptions::default())
+            .unwrap();
+        writer.write_all(b"abc").unwrap();
+        let zip = writer.finish().unwrap();
+
+        let hashes = from_zip(Cursor::new(zip.into_inner())).unwrap();
+        assert_eq!(hashes.crc32, "352441c2");
+    }
+}
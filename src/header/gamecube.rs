@@ -0,0 +1,32 @@
+use super::*;
+use crate::archive;
+
+const HEADER_LEN: usize = 0x60;
+const WII_MAGIC: [u8; 4] = [0x5D, 0x1C, 0x9E, 0xA3];
+const GAMECUBE_MAGIC: [u8; 4] = [0xC2, 0x33, 0x9F, 0x3D];
+
+// GameCube and Wii disc images share the same boot header
+pub fn read(path: &Path) -> Result<Option<Header>> {
+    let data = archive::read_at(path, 0, HEADER_LEN)?;
+    Ok(parse(&data))
+}
+
+fn parse(data: &[u8]) -> Option<Header> {
+    if data.len() < HEADER_LEN {
+        return None;
+    }
+
+    if data[0x18..0x1C] != WII_MAGIC && data[0x1C..0x20] != GAMECUBE_MAGIC {
+        return None;
+    }
+
+    let code = text(&data[..0x04])?;
+    let title = text(&data[0x20..0x60]);
+
+    Some(Header {
+        name: title.clone(),
+        title,
+        region: code.chars().last().and_then(nintendo_region),
+        code: Some(code),
+    })
+}
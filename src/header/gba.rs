@@ -0,0 +1,58 @@
+use super::*;
+use crate::archive;
+
+const HEADER_LEN: usize = 0xC0;
+
+pub fn read(path: &Path) -> Result<Option<Header>> {
+    let data = archive::read_at(path, 0, HEADER_LEN)?;
+    Ok(parse(&data))
+}
+
+fn parse(data: &[u8]) -> Option<Header> {
+    if data.len() < HEADER_LEN || data[0xB2] != 0x96 {
+        return None;
+    }
+
+    let checksum = data[0xA0..0xBD]
+        .iter()
+        .fold(0u8, |acc, b| acc.wrapping_sub(*b))
+        .wrapping_sub(0x19);
+    if checksum != data[0xBD] {
+        return None;
+    }
+
+    let code = text(&data[0xAC..0xB0])?;
+
+    Some(Header {
+        title: None,
+        name: text(&data[0xA0..0xAC]),
+        region: code.chars().last().and_then(nintendo_region),
+        code: Some(code),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let mut data = vec![0; HEADER_LEN];
+        data[0xA0..0xAC].copy_from_slice(b"POKEMON EMER");
+        data[0xAC..0xB0].copy_from_slice(b"BPEE");
+        data[0xB0..0xB2].copy_from_slice(b"01");
+        data[0xB2] = 0x96;
+        data[0xBD] = data[0xA0..0xBD]
+            .iter()
+            .fold(0u8, |acc, b| acc.wrapping_sub(*b))
+            .wrapping_sub(0x19);
+
+        let header = parse(&data).unwrap();
+        assert_eq!(header.name.as_deref(), Some("POKEMON EMER"));
+        assert_eq!(header.code.as_deref(), Some("BPEE"));
+        assert_eq!(header.region.as_deref(), Some("USA"));
+
+        data[0xBD] = data[0xBD].wrapping_add(1);
+        assert_eq!(parse(&data), None);
+    }
+}
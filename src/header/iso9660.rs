@@ -0,0 +1,230 @@
+use crate::prelude::*;
+use std::io::{Read, Seek, SeekFrom};
+
+const SECTOR: u64 = 2048;
+const RAW_SECTOR: u64 = 2352;
+const PVD_SECTOR: u64 = 16;
+
+// far more than any directory or header file this reads, e.g. PARAM.SFO
+const MAX_EXTENT: u32 = 1024 * 1024;
+
+// layouts of plain .iso files and raw mode 1 / mode 2 .bin tracks
+const LAYOUTS: [(u64, u64); 3] = [(SECTOR, 0), (RAW_SECTOR, 24), (RAW_SECTOR, 16)];
+
+struct Record {
+    name: String,
+    lba: u32,
+    size: u32,
+    is_dir: bool,
+}
+
+pub struct Image<R> {
+    reader: R,
+    sector_size: u64,
+    data_offset: u64,
+    root: Record,
+}
+
+impl<R: Read + Seek> Image<R> {
+    pub fn open(mut reader: R) -> Result<Self> {
+        for (sector_size, data_offset) in LAYOUTS {
+            let mut pvd = vec![0; SECTOR as usize];
+            reader.seek(SeekFrom::Start(PVD_SECTOR * sector_size + data_offset))?;
+            if reader.read_exact(&mut pvd).is_err() {
+                continue;
+            }
+
+            if pvd[0] != 1 || &pvd[1..6] != b"CD001" {
+                continue;
+            }
+
+            let root = parse_record(&pvd[156..190]).context("invalid root record")?;
+
+            return Ok(Self {
+                reader,
+                sector_size,
+                data_offset,
+                root,
+            });
+        }
+
+        Err(anyhow!("not an ISO9660 image"))
+    }
+
+    pub fn read_file(&mut self, path: &str) -> Result<Option<Vec<u8>>> {
+        let mut lba = self.root.lba;
+        let mut size = self.root.size;
+
+        let components: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+
+        for (i, component) in components.iter().enumerate() {
+            let dir = self.read_extent(lba, size)?;
+            let is_last = i == components.len() - 1;
+
+            let record = records(&dir)
+                .find(|r| r.name.eq_ignore_ascii_case(component) && r.is_dir != is_last);
+
+            match record {
+                Some(r) => {
+                    lba = r.lba;
+                    size = r.size;
+                }
+                None => return Ok(None),
+            }
+        }
+
+        self.read_extent(lba, size).map(Some)
+    }
+
+    fn read_extent(&mut self, lba: u32, size: u32) -> Result<Vec<u8>> {
+        // sizes come from the image, which may be corrupt
+        if size > MAX_EXTENT {
+            return Err(anyhow!("extent of {} bytes is too large", size));
+        }
+
+        let mut data = Vec::with_capacity(size as usize);
+        let mut sector = vec![0; SECTOR as usize];
+        let mut remaining = size as usize;
+        let mut lba = lba as u64;
+
+        while remaining > 0 {
+            self.reader
+                .seek(SeekFrom::Start(lba * self.sector_size + self.data_offset))?;
+            self.reader.read_exact(&mut sector)?;
+            let n = remaining.min(SECTOR as usize);
+            data.extend_from_slice(&sector[..n]);
+            remaining -= n;
+            lba += 1;
+        }
+
+        Ok(data)
+    }
+}
+
+fn records(dir: &[u8]) -> impl Iterator<Item = Record> + '_ {
+    let mut pos = 0;
+    std::iter::from_fn(move || loop {
+        if pos >= dir.len() {
+            return None;
+        }
+
+        let len = dir[pos] as usize;
+        if len == 0 {
+            // records don't cross sector boundaries
+            pos = (pos / SECTOR as usize + 1) * SECTOR as usize;
+            continue;
+        }
+
+        let record = dir.get(pos..pos + len).and_then(parse_record);
+        pos += len;
+
+        if let Some(r) = record {
+            return Some(r);
+        }
+    })
+}
+
+fn parse_record(data: &[u8]) -> Option<Record> {
+    let name_len = *data.get(32)? as usize;
+    let name = data.get(33..33 + name_len)?;
+    let name = String::from_utf8_lossy(name);
+    let name = name.split(';').next().unwrap_or_default().to_owned();
+
+    Some(Record {
+        name,
+        lba: super::u32_le(data, 2)?,
+        size: super::u32_le(data, 10)?,
+        is_dir: data.get(25)? & 0x02 != 0,
+    })
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    fn record(name: &str, lba: u32, size: u32, is_dir: bool) -> Vec<u8> {
+        let len = 33 + name.len() + (1 - name.len() % 2);
+        let mut r = vec![0; len];
+        r[0] = len as u8;
+        r[2..6].copy_from_slice(&lba.to_le_bytes());
+        r[10..14].copy_from_slice(&size.to_le_bytes());
+        r[25] = if is_dir { 0x02 } else { 0x00 };
+        r[32] = name.len() as u8;
+        r[33..33 + name.len()].copy_from_slice(name.as_bytes());
+        r
+    }
+
+    // builds a 2048-byte sector image with a single file at `path`
+    pub fn build(path: &str, content: &[u8]) -> Vec<u8> {
+        let mut image = vec![0; SECTOR as usize * 21];
+        let sector = |n: u64| (n * SECTOR) as usize;
+
+        let pvd = sector(PVD_SECTOR);
+        image[pvd] = 1;
+        image[pvd + 1..pvd + 6].copy_from_slice(b"CD001");
+        let root = record("\0", 18, SECTOR as u32, true);
+        image[pvd + 156..pvd + 156 + root.len()].copy_from_slice(&root);
+
+        let (dir, file) = match path.split_once('/') {
+            Some((dir, file)) => (Some(dir), file),
+            None => (None, path),
+        };
+        let file = record(&format!("{};1", file), 20, content.len() as u32, false);
+
+        let mut root_dir = record("\0", 18, SECTOR as u32, true);
+        root_dir.extend(record("\u{1}", 18, SECTOR as u32, true));
+        match dir {
+            Some(dir) => {
+                root_dir.extend(record(dir, 19, SECTOR as u32, true));
+                image[sector(19)..sector(19) + file.len()].copy_from_slice(&file);
+            }
+            None => root_dir.extend(file),
+        }
+        image[sector(18)..sector(18) + root_dir.len()].copy_from_slice(&root_dir);
+
+        image[sector(20)..sector(20) + content.len()].copy_from_slice(content);
+
+        image
+    }
+
+    // wraps every sector as in a raw mode 2 .bin track
+    pub fn to_raw(image: &[u8]) -> Vec<u8> {
+        image
+            .chunks(SECTOR as usize)
+            .flat_map(|s| {
+                let mut raw = vec![0; 24];
+                raw.extend_from_slice(s);
+                raw.resize(RAW_SECTOR as usize, 0);
+                raw
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_read_file() {
+        use std::io::Cursor;
+
+        let image = build("PSP_GAME/PARAM.SFO", b"hello");
+        let mut iso = Image::open(Cursor::new(image)).unwrap();
+
+        assert_eq!(
+            iso.read_file("PSP_GAME/PARAM.SFO").unwrap().as_deref(),
+            Some(&b"hello"[..])
+        );
+        assert_eq!(iso.read_file("PSP_GAME/ICON0.PNG").unwrap(), None);
+        assert_eq!(iso.read_file("PARAM.SFO").unwrap(), None);
+    }
+
+    #[test]
+    fn test_read_file_too_large() {
+        use std::io::Cursor;
+
+        let mut image = build("PARAM.SFO", b"hello");
+        // the size of the file record, after the . and .. records of the root directory
+        let size_pos = SECTOR as usize * 18 + 34 * 2 + 10;
+        image[size_pos..size_pos + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        let mut iso = Image::open(Cursor::new(image)).unwrap();
+        assert!(iso.read_file("PARAM.SFO").is_err());
+    }
+}
@@ -0,0 +1,118 @@
+mod gamecube;
+mod gba;
+mod iso9660;
+mod n3ds;
+mod n64;
+mod nds;
+mod playstation;
+pub mod ps3;
+mod psp;
+mod sfo;
+mod snes;
+
+use crate::platform::Platform;
+use crate::prelude::*;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Header {
+    pub title: Option<String>, // full title, good enough for searching
+    pub name: Option<String>,  // raw internal name, often abbreviated
+    pub code: Option<String>,
+    pub region: Option<String>,
+}
+
+pub fn read(path: &Path, platform: Platform) -> Result<Option<Header>> {
+    use Platform::*;
+    match platform {
+        GameBoyAdvance => gba::read(path),
+        NintendoDS | NintendoDsi => nds::read(path),
+        Nintendo3DS => n3ds::read(path),
+        GameCube | Wii => gamecube::read(path),
+        Nintendo64 => n64::read(path),
+        SuperNintendo => snes::read(path),
+        PlayStation | PlayStation2 => playstation::read(path),
+        Psp => psp::read(path),
+        PlayStation3 => ps3::read(path),
+        _ => Ok(None),
+    }
+}
+
+// printable ASCII up to the first NUL, trimmed
+fn text(bytes: &[u8]) -> Option<String> {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    let bytes = &bytes[..end];
+
+    if !bytes.iter().all(|b| (0x20..=0x7e).contains(b)) {
+        return None;
+    }
+
+    let s = String::from_utf8_lossy(bytes).trim().to_owned();
+    if s.is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
+
+// internal names are uppercase and space padded, e.g. "FINAL FANTASY III   "
+fn title_case(name: &str) -> String {
+    let words: Vec<String> = name
+        .split_whitespace()
+        .map(|word| {
+            let is_numeral = word.chars().all(|c| matches!(c, 'I' | 'V' | 'X'));
+            if is_numeral || word.chars().any(char::is_lowercase) {
+                return word.to_owned();
+            }
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_string() + &chars.as_str().to_lowercase(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    words.join(" ")
+}
+
+fn u16_le(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn u32_le(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+// last character of Nintendo game codes, e.g. AGB-BPEE
+fn nintendo_region(c: char) -> Option<String> {
+    let region = match c {
+        'A' => "World",
+        'E' => "USA",
+        'J' => "Japan",
+        'P' | 'X' | 'Y' | 'Z' => "Europe",
+        'D' => "Germany",
+        'F' => "France",
+        'I' => "Italy",
+        'S' => "Spain",
+        'H' => "Netherlands",
+        'K' => "Korea",
+        'U' => "Australia",
+        'C' => "China",
+        'R' => "Russia",
+        _ => return None,
+    };
+    Some(region.into())
+}
+
+// third character of PlayStation serials, e.g. SLUS-00561, ULES-00151, BLUS31604
+fn serial_region(serial: &str) -> Option<String> {
+    let region = match serial.chars().nth(2)? {
+        'U' => "USA",
+        'E' => "Europe",
+        'J' | 'P' => "Japan",
+        'A' => "Asia",
+        'K' => "Korea",
+        _ => return None,
+    };
+    Some(region.into())
+}
@@ -0,0 +1,36 @@
+use super::*;
+use crate::archive;
+
+const MEDIA_UNIT: u64 = 0x200;
+
+// .3ds/.cci images; the title itself lives in the (usually encrypted) ExeFS
+pub fn read(path: &Path) -> Result<Option<Header>> {
+    let ncsd = archive::read_at(path, 0, 0x200)?;
+    if ncsd.get(0x100..0x104) != Some(b"NCSD") {
+        return Ok(None);
+    }
+
+    let partition_offset = match u32_le(&ncsd, 0x120) {
+        Some(offset) => offset as u64 * MEDIA_UNIT,
+        None => return Ok(None),
+    };
+
+    let ncch = archive::read_at(path, partition_offset, 0x200)?;
+    Ok(parse_ncch(&ncch))
+}
+
+fn parse_ncch(ncch: &[u8]) -> Option<Header> {
+    if ncch.get(0x100..0x104) != Some(b"NCCH") {
+        return None;
+    }
+
+    // e.g. CTR-P-EGDE
+    let code = text(ncch.get(0x150..0x160)?)?;
+
+    Some(Header {
+        title: None,
+        name: None,
+        region: code.chars().last().and_then(nintendo_region),
+        code: Some(code),
+    })
+}
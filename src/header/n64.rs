@@ -0,0 +1,59 @@
+use super::*;
+use crate::archive;
+
+const HEADER_LEN: usize = 0x40;
+
+pub fn read(path: &Path) -> Result<Option<Header>> {
+    let data = archive::read_at(path, 0, HEADER_LEN)?;
+    Ok(parse(&data))
+}
+
+fn parse(data: &[u8]) -> Option<Header> {
+    if data.len() < HEADER_LEN {
+        return None;
+    }
+
+    // .z64 is big-endian, .v64 is byte-swapped and .n64 is little-endian
+    let data: Vec<u8> = match data[..4] {
+        [0x80, 0x37, 0x12, 0x40] => data.to_vec(),
+        [0x37, 0x80, 0x40, 0x12] => data.chunks_exact(2).flat_map(|c| [c[1], c[0]]).collect(),
+        [0x40, 0x12, 0x37, 0x80] => data
+            .chunks_exact(4)
+            .flat_map(|c| [c[3], c[2], c[1], c[0]])
+            .collect(),
+        _ => return None,
+    };
+
+    let code = text(&data[0x3B..0x3F]);
+    let name = text(&data[0x20..0x34]);
+
+    // 20 characters are usually enough for the full title
+    Some(Header {
+        title: name.as_deref().map(super::title_case),
+        name,
+        region: nintendo_region(data[0x3E] as char),
+        code,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let mut data = vec![0; HEADER_LEN];
+        data[..4].copy_from_slice(&[0x80, 0x37, 0x12, 0x40]);
+        data[0x20..0x34].copy_from_slice(b"SUPER MARIO 64      ");
+        data[0x3B..0x3F].copy_from_slice(b"NSME");
+
+        let header = parse(&data).unwrap();
+        assert_eq!(header.name.as_deref(), Some("SUPER MARIO 64"));
+        assert_eq!(header.title.as_deref(), Some("Super Mario 64"));
+        assert_eq!(header.code.as_deref(), Some("NSME"));
+        assert_eq!(header.region.as_deref(), Some("USA"));
+
+        let swapped: Vec<u8> = data.chunks_exact(2).flat_map(|c| [c[1], c[0]]).collect();
+        assert_eq!(parse(&swapped), Some(header));
+    }
+}
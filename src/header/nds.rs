@@ -0,0 +1,108 @@
+use super::*;
+use crate::archive;
+
+const HEADER_LEN: usize = 0x160;
+const BANNER_LEN: usize = 0x440;
+const ENGLISH_TITLE: usize = 0x340;
+
+pub fn read(path: &Path) -> Result<Option<Header>> {
+    let data = archive::read_at(path, 0, HEADER_LEN)?;
+
+    let mut header = match parse(&data) {
+        Some(header) => header,
+        None => return Ok(None),
+    };
+
+    let banner_offset = u32_le(&data, 0x68).unwrap_or(0);
+    if banner_offset != 0 {
+        let banner = archive::read_at(path, banner_offset as u64, BANNER_LEN)?;
+        header.title = banner_title(&banner);
+    }
+
+    Ok(Some(header))
+}
+
+fn parse(data: &[u8]) -> Option<Header> {
+    if data.len() < HEADER_LEN || crc16(&data[..0x15E]) != u16_le(data, 0x15E)? {
+        return None;
+    }
+
+    let code = text(&data[0x0C..0x10])?;
+
+    Some(Header {
+        title: None,
+        name: text(&data[..0x0C]),
+        region: code.chars().last().and_then(nintendo_region),
+        code: Some(code),
+    })
+}
+
+// UTF-16 lines, e.g. "New Super Mario Bros.\nNintendo" or "Title\nSubtitle\nPublisher"
+fn banner_title(banner: &[u8]) -> Option<String> {
+    let units: Vec<u16> = banner
+        .get(ENGLISH_TITLE..ENGLISH_TITLE + 0x100)?
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|&u| u != 0)
+        .collect();
+
+    let text = String::from_utf16(&units).ok()?;
+    let lines: Vec<&str> = text.lines().map(|l| l.trim()).collect();
+
+    let title = match lines.as_slice() {
+        [title, subtitle, _publisher] => format!("{} - {}", title, subtitle),
+        [title, ..] => title.to_string(),
+        [] => return None,
+    };
+
+    if title.is_empty() {
+        None
+    } else {
+        Some(title)
+    }
+}
+
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for &b in data {
+        crc ^= b as u16;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xA001
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let mut data = vec![0; HEADER_LEN];
+        data[..0x0C].copy_from_slice(b"NEW MARIO\0\0\0");
+        data[0x0C..0x10].copy_from_slice(b"A2DE");
+        let crc = crc16(&data[..0x15E]);
+        data[0x15E..0x160].copy_from_slice(&crc.to_le_bytes());
+
+        let header = parse(&data).unwrap();
+        assert_eq!(header.name.as_deref(), Some("NEW MARIO"));
+        assert_eq!(header.code.as_deref(), Some("A2DE"));
+        assert_eq!(header.region.as_deref(), Some("USA"));
+
+        let mut banner = vec![0; BANNER_LEN];
+        let title: Vec<u8> = "Pokémon Mystery Dungeon\nExplorers of Sky\nNintendo"
+            .encode_utf16()
+            .flat_map(|u| u.to_le_bytes())
+            .collect();
+        banner[ENGLISH_TITLE..ENGLISH_TITLE + title.len()].copy_from_slice(&title);
+        assert_eq!(
+            banner_title(&banner).as_deref(),
+            Some("Pokémon Mystery Dungeon - Explorers of Sky")
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use super::iso9660::Image;
+use super::*;
+use crate::descriptor;
+
+static SYSTEM_CNF: &str = "SYSTEM.CNF";
+
+// PS1 and PS2 discs boot the executable named after their serial
+pub fn read(path: &Path) -> Result<Option<Header>> {
+    let path = if descriptor::is_descriptor(path) {
+        match descriptor::tracks(path)?.into_iter().next() {
+            Some(track) => track,
+            None => return Ok(None),
+        }
+    } else {
+        path.to_owned()
+    };
+
+    let file = File::open(path)?;
+
+    // e.g. .chd files
+    let mut iso = match Image::open(file) {
+        Ok(iso) => iso,
+        Err(_) => return Ok(None),
+    };
+
+    let cnf = match iso.read_file(SYSTEM_CNF)? {
+        Some(cnf) => cnf,
+        None => return Ok(None),
+    };
+
+    let code = serial(&String::from_utf8_lossy(&cnf));
+
+    Ok(Some(Header {
+        title: None,
+        name: None,
+        region: code.as_deref().and_then(serial_region),
+        code,
+    }))
+}
+
+// BOOT = cdrom:\SLUS_005.61;1 -> SLUS-00561
+fn serial(cnf: &str) -> Option<String> {
+    let boot = cnf.lines().find_map(|l| {
+        let (key, value) = l.split_once('=')?;
+        match key.trim() {
+            "BOOT" | "BOOT2" => Some(value.trim()),
+            _ => None,
+        }
+    })?;
+
+    let executable = boot.rsplit(['\\', ':', '/']).next()?.split(';').next()?;
+
+    let serial = executable.replace('.', "").replace('_', "-");
+    if serial.len() < 9 {
+        return None;
+    }
+
+    Some(serial.to_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_serial() {
+        let ps1 = "BOOT = cdrom:\\SLUS_005.61;1\r\nTCB = 4\r\nEVENT = 10\r\n";
+        assert_eq!(serial(ps1).as_deref(), Some("SLUS-00561"));
+
+        let ps2 = "BOOT2 = cdrom0:\\SLES_541.64;1\nVER = 1.00\nVMODE = PAL\n";
+        assert_eq!(serial(ps2).as_deref(), Some("SLES-54164"));
+
+        assert_eq!(serial("VMODE = NTSC"), None);
+    }
+
+    #[test]
+    fn test_read_raw() {
+        let image = iso9660::tests::build("SYSTEM.CNF", b"BOOT = cdrom:\\SLUS_005.61;1\r\n");
+        let raw = iso9660::tests::to_raw(&image);
+
+        let mut iso = Image::open(Cursor::new(raw)).unwrap();
+        let cnf = iso.read_file(SYSTEM_CNF).unwrap().unwrap();
+        assert_eq!(
+            serial(&String::from_utf8_lossy(&cnf)).as_deref(),
+            Some("SLUS-00561")
+        );
+    }
+}
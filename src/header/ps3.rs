@@ -0,0 +1,42 @@
+use super::*;
+use std::fs;
+
+static GAME_FOLDER: &str = "PS3_GAME";
+static SFO: &str = "PARAM.SFO";
+
+// games are folders such as BLUS31604-[Persona 5]/PS3_GAME/USRDIR/EBOOT.BIN
+pub fn game_dir(path: &Path) -> Option<&Path> {
+    path.ancestors()
+        .find(|p| {
+            p.file_name()
+                .unwrap_or_default()
+                .to_string()
+                .eq_ignore_ascii_case(GAME_FOLDER)
+        })
+        .and_then(|p| p.parent())
+}
+
+pub fn read(path: &Path) -> Result<Option<Header>> {
+    let sfo_path = path.join(GAME_FOLDER).join(SFO);
+    if !sfo_path.exists() {
+        return Ok(None);
+    }
+
+    let sfo = fs::read(sfo_path)?;
+    Ok(psp::from_sfo(&sfo))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_dir() {
+        let eboot = Path::new("ps3/BLUS31604-[Persona 5]/PS3_GAME/USRDIR/EBOOT.BIN");
+        assert_eq!(
+            game_dir(eboot),
+            Some(Path::new("ps3/BLUS31604-[Persona 5]"))
+        );
+        assert_eq!(game_dir(Path::new("gba/Pokemon Emerald.zip")), None);
+    }
+}
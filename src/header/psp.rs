@@ -0,0 +1,63 @@
+use super::iso9660::Image;
+use super::*;
+
+pub fn read(path: &Path) -> Result<Option<Header>> {
+    let file = File::open(path)?;
+
+    // e.g. .cso files
+    let mut iso = match Image::open(file) {
+        Ok(iso) => iso,
+        Err(_) => return Ok(None),
+    };
+
+    let sfo = match iso.read_file("PSP_GAME/PARAM.SFO")? {
+        Some(sfo) => sfo,
+        None => return Ok(None),
+    };
+
+    Ok(from_sfo(&sfo))
+}
+
+pub fn from_sfo(sfo: &[u8]) -> Option<Header> {
+    let values = sfo::parse(sfo)?;
+
+    let title = values.get("TITLE").filter(|t| !t.is_empty()).cloned();
+    let code = values
+        .get("DISC_ID")
+        .or_else(|| values.get("TITLE_ID"))
+        .filter(|c| !c.is_empty())
+        .cloned();
+
+    Some(Header {
+        name: title.clone(),
+        title,
+        region: code.as_deref().and_then(serial_region),
+        code,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read() {
+        let sfo = sfo::tests::build(&[
+            ("DISC_ID", "ULUS10391"),
+            ("TITLE", "Monster Hunter Freedom Unite"),
+        ]);
+        let image = iso9660::tests::build("PSP_GAME/PARAM.SFO", &sfo);
+
+        let mut iso = Image::open(Cursor::new(image)).unwrap();
+        let sfo = iso.read_file("PSP_GAME/PARAM.SFO").unwrap().unwrap();
+        let header = from_sfo(&sfo).unwrap();
+
+        assert_eq!(
+            header.title.as_deref(),
+            Some("Monster Hunter Freedom Unite")
+        );
+        assert_eq!(header.code.as_deref(), Some("ULUS10391"));
+        assert_eq!(header.region.as_deref(), Some("USA"));
+    }
+}
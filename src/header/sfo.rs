@@ -0,0 +1,90 @@
+use super::{u16_le, u32_le};
+use crate::prelude::*;
+
+const MAGIC: &[u8] = b"\0PSF";
+const FORMAT_INT: u16 = 0x0404;
+
+// PARAM.SFO key/value tables, as found in PSP, PS3 and Vita games
+pub fn parse(data: &[u8]) -> Option<HashMap<String, String>> {
+    if data.get(..4)? != MAGIC {
+        return None;
+    }
+
+    let key_table = u32_le(data, 0x08)? as usize;
+    let data_table = u32_le(data, 0x0C)? as usize;
+    let count = u32_le(data, 0x10)? as usize;
+
+    let mut values = HashMap::new();
+
+    for i in 0..count {
+        let entry = 0x14 + i * 0x10;
+        let key_offset = key_table + u16_le(data, entry)? as usize;
+        let format = u16_le(data, entry + 0x02)?;
+        let len = u32_le(data, entry + 0x04)? as usize;
+        let data_offset = data_table + u32_le(data, entry + 0x0C)? as usize;
+
+        let key = data.get(key_offset..)?;
+        let key_end = key.iter().position(|&b| b == 0)?;
+        let key = String::from_utf8_lossy(&key[..key_end]).into_owned();
+
+        let value = data.get(data_offset..data_offset + len)?;
+        let value = if format == FORMAT_INT {
+            u32_le(value, 0)?.to_string()
+        } else {
+            let end = value.iter().position(|&b| b == 0).unwrap_or(value.len());
+            String::from_utf8_lossy(&value[..end]).trim().to_owned()
+        };
+
+        values.insert(key, value);
+    }
+
+    Some(values)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn build(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut keys = vec![];
+        let mut values = vec![];
+        let mut index = vec![];
+
+        for (key, value) in entries {
+            let value_len = value.len() as u32 + 1;
+            index.extend((keys.len() as u16).to_le_bytes());
+            index.extend(0x0204u16.to_le_bytes());
+            index.extend(value_len.to_le_bytes());
+            index.extend(value_len.to_le_bytes());
+            index.extend((values.len() as u32).to_le_bytes());
+            keys.extend(key.as_bytes());
+            keys.push(0);
+            values.extend(value.as_bytes());
+            values.push(0);
+        }
+
+        let key_table = 0x14 + index.len() as u32;
+        let data_table = key_table + keys.len() as u32;
+
+        let mut sfo = MAGIC.to_vec();
+        sfo.extend(0x0101u32.to_le_bytes());
+        sfo.extend(key_table.to_le_bytes());
+        sfo.extend(data_table.to_le_bytes());
+        sfo.extend((entries.len() as u32).to_le_bytes());
+        sfo.extend(index);
+        sfo.extend(keys);
+        sfo.extend(values);
+        sfo
+    }
+
+    #[test]
+    fn test_parse() {
+        let sfo = build(&[("TITLE", "Persona 5"), ("TITLE_ID", "BLUS31604")]);
+        let values = parse(&sfo).unwrap();
+        assert_eq!(values.get("TITLE").map(|s| s.as_str()), Some("Persona 5"));
+        assert_eq!(
+            values.get("TITLE_ID").map(|s| s.as_str()),
+            Some("BLUS31604")
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use super::*;
+use crate::archive;
+
+const COPIER_HEADER_LEN: usize = 0x200;
+const LOROM: usize = 0x7FC0;
+const HIROM: usize = 0xFFC0;
+const READ_LEN: usize = HIROM + COPIER_HEADER_LEN + 0x40;
+
+pub fn read(path: &Path) -> Result<Option<Header>> {
+    let data = archive::read_at(path, 0, READ_LEN)?;
+    Ok(parse(&data))
+}
+
+fn parse(data: &[u8]) -> Option<Header> {
+    let bases = [
+        LOROM,
+        HIROM,
+        LOROM + COPIER_HEADER_LEN,
+        HIROM + COPIER_HEADER_LEN,
+    ];
+
+    bases.iter().find_map(|&base| parse_at(data, base))
+}
+
+fn parse_at(data: &[u8], base: usize) -> Option<Header> {
+    let complement = u16_le(data, base + 0x1C)?;
+    let checksum = u16_le(data, base + 0x1E)?;
+    if checksum ^ complement != 0xFFFF {
+        return None;
+    }
+
+    let name = text(&data[base..base + 21])?;
+
+    // only present in the extended header
+    let code = if data[base + 0x1A] == 0x33 {
+        text(&data[base - 0x0E..base - 0x0A])
+    } else {
+        None
+    };
+
+    Some(Header {
+        title: Some(super::title_case(&name)),
+        name: Some(name),
+        code,
+        region: region(data[base + 0x19]),
+    })
+}
+
+fn region(destination: u8) -> Option<String> {
+    let region = match destination {
+        0x00 => "Japan",
+        0x01 => "USA",
+        0x02 => "Europe",
+        0x03 => "Sweden",
+        0x06 => "France",
+        0x07 => "Netherlands",
+        0x08 => "Spain",
+        0x09 => "Germany",
+        0x0A => "Italy",
+        0x0B => "China",
+        0x0D => "Korea",
+        0x0F => "Canada",
+        0x10 => "Brazil",
+        0x11 => "Australia",
+        _ => return None,
+    };
+    Some(region.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let mut data = vec![0; READ_LEN];
+        let base = HIROM + COPIER_HEADER_LEN;
+        data[base..base + 21].copy_from_slice(b"THE LEGEND OF ZELDA  ");
+        data[base + 0x19] = 0x01;
+        data[base + 0x1C..base + 0x1E].copy_from_slice(&0x5F3Fu16.to_le_bytes());
+        data[base + 0x1E..base + 0x20].copy_from_slice(&0xA0C0u16.to_le_bytes());
+
+        let header = parse(&data).unwrap();
+        assert_eq!(header.name.as_deref(), Some("THE LEGEND OF ZELDA"));
+        assert_eq!(header.title.as_deref(), Some("The Legend Of Zelda"));
+        assert_eq!(header.region.as_deref(), Some("USA"));
+        assert_eq!(header.code, None);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::prelude::*;
+use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::header::RETRY_AFTER;
+use reqwest::StatusCode;
+use std::fmt;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+static BASE_DELAY: Duration = Duration::from_millis(500);
+static MAX_DELAY: Duration = Duration::from_secs(60);
+
+// a request that would need the network, with --offline
+#[derive(Debug)]
+pub struct Offline;
+
+impl fmt::Display for Offline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("skipped: offline")
+    }
+}
+
+impl std::error::Error for Offline {}
+
+pub fn ensure_online(config: &Config) -> Result<()> {
+    if config.cli.offline {
+        Err(Offline.into())
+    } else {
+        Ok(())
+    }
+}
+
+// token bucket, shared by all workers
+pub struct RateLimiter {
+    per_second: Option<f64>, // none for no limit
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl RateLimiter {
+    // 0 or less for no limit
+    pub fn new(per_second: f32) -> Self {
+        let per_second = Some(f64::from(per_second)).filter(|&n| n > 0.0);
+        Self {
+            per_second,
+            bucket: Mutex::new(Bucket {
+                tokens: per_second.unwrap_or_default().max(1.0),
+                updated_at: Instant::now(),
+            }),
+        }
+    }
+
+    // blocks until a request is allowed
+    pub fn acquire(&self) {
+        let per_second = match self.per_second {
+            Some(per_second) => per_second,
+            None => return,
+        };
+
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().expect("poisoned rate limiter");
+                let now = Instant::now();
+                let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+                bucket.tokens = (bucket.tokens + elapsed * per_second).min(per_second.max(1.0));
+                bucket.updated_at = now;
+
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return;
+                }
+
+                (1.0 - bucket.tokens) / per_second
+            };
+            thread::sleep(Duration::from_secs_f64(wait));
+        }
+    }
+}
+
+// retries on 429 and 5xx, honoring Retry-After
+pub fn send(
+    request: RequestBuilder,
+    limiter: Option<&RateLimiter>,
+    max_retries: u32,
+) -> Result<Response> {
+    let mut attempt = 0;
+
+    loop {
+        let attempt_request = request.try_clone().context("request can't be retried")?;
+        if let Some(limiter) = limiter {
+            limiter.acquire();
+        }
+        let response = attempt_request.send()?;
+
+        let status = response.status();
+        let is_retriable = status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error();
+        if !is_retriable || attempt >= max_retries {
+            return response.error_for_status().map_err(|e| e.into());
+        }
+
+        let delay = retry_after(&response).unwrap_or_else(|| backoff(attempt));
+        info!(
+            msg = "retrying",
+            status = status.as_u16(),
+            attempt,
+            delay_ms = delay.as_millis() as u64
+        );
+        thread::sleep(delay);
+        attempt += 1;
+    }
+}
+
+// only the delay-seconds form
+fn retry_after(response: &Response) -> Option<Duration> {
+    let secs: u64 = response
+        .headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(secs).min(MAX_DELAY))
+}
+
+// exponential, with jitter so that workers don't retry in lockstep
+fn backoff(attempt: u32) -> Duration {
+    let delay = BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_DELAY);
+    delay.mul_f64(0.5 + fastrand::f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        for attempt in 0..20 {
+            let expected = BASE_DELAY
+                .saturating_mul(2u32.saturating_pow(attempt))
+                .min(MAX_DELAY);
+            let delay = backoff(attempt);
+            assert!(delay >= expected / 2);
+            assert!(delay <= expected.mul_f64(1.5));
+        }
+
+        // so that workers don't retry in lockstep
+        let delays: HashSet<Duration> = (0..10).map(|_| backoff(3)).collect();
+        assert!(delays.len() > 1);
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let limiter = RateLimiter::new(20.0);
+        let start = Instant::now();
+        for _ in 0..30 {
+            limiter.acquire();
+        }
+        // the first 20 are a burst, the other 10 take half a second
+        assert!(start.elapsed() >= Duration::from_millis(450));
+    }
+
+    #[test]
+    fn test_rate_limiter_unlimited() {
+        let limiter = RateLimiter::new(0.0);
+        let start = Instant::now();
+        for _ in 0..100 {
+            limiter.acquire();
+        }
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+}
@@ -1,38 +1,65 @@
 use super::*;
+use crate::cache::Cache;
+use crate::config::yaml::Igdb;
 use crate::dns_common::deser::json_from_str;
+use crate::http::{self, RateLimiter};
 use crate::platform::Platform;
 use crate::prelude::*;
+use crate::provider::{self, ImageKind, MetadataProvider};
 use crate::twitch;
 use reqwest::blocking::Client as HttpClient;
 use reqwest::header;
 use std::fs;
 use std::io::Cursor;
+use std::sync::Mutex;
+
+static FIELDS: &str = "id,name,rating,aggregated_rating,cover.image_id,artworks.image_id,screenshots.image_id,first_release_date,genres.name,involved_companies.company.name,storyline,summary,alternative_names.name,game_localizations.name,platforms.name,release_dates.region";
+
+static GAMES_ENDPOINT: &str = "/v4/games";
+
+// how much worse a match released in the wanted region may be
+static REGION_MARGIN: f32 = 0.05;
+
+// IGDB's limit of named queries per multiquery
+static MULTIQUERY_SIZE: usize = 10;
 
 pub struct Client {
     config: Arc<Config>,
     http: HttpClient,
+    limiter: RateLimiter,
+    cache: Arc<Cache>,
+    prefetched: Mutex<HashMap<String, Vec<Game>>>, // by search query
 }
 
 impl Client {
-    pub fn get_metadata(&self, name: &str, platform: Platform) -> Result<Game> {
-        let platform_id: PlatformId = platform.try_into()?;
+    fn igdb_config(&self) -> &Igdb {
+        &self.config.yaml.igdb
+    }
 
-        let simple_name = name.replace('é', "e").replace(':', " ").replace(" - ", " ");
+    // ranked by how closely they match the name, best first;
+    // the region, e.g. of a disc serial, breaks near ties between regional releases
+    pub fn search(
+        &self,
+        name: &str,
+        platform: Platform,
+        region: Option<&str>,
+    ) -> Result<Vec<(Game, provider::Match)>> {
+        let platform_id: PlatformId = platform.try_into()?;
 
-        let body = format!(
-            r#"fields id,name,rating,aggregated_rating,cover.image_id,artworks.image_id,screenshots.image_id,first_release_date,genres.name,involved_companies.company.name,storyline,summary;
-    search "{}";
-    where platforms = ({});
-    limit 4;"#,
-            simple_name.trim(),
-            platform_id.query_str(),
-        );
+        let simple_name = simple_name(name);
 
-        let uri = format!("{}/v4/games", self.config.yaml.igdb.api_base_url);
-        let response = self.http.post(uri).body(body.clone()).send()?;
-        let text = response.text()?;
+        let body = search_query(&simple_name, platform_id);
 
-        let games: Vec<Game> = json_from_str(&text)?;
+        let prefetched = self
+            .prefetched
+            .lock()
+            .map_err(|_| anyhow!("poisoned prefetch cache"))?
+            .get(&body)
+            .cloned();
+        let games = match prefetched {
+            Some(games) => games,
+            None => self.query_games(body)?,
+        };
 
         let denylisted_name_substrings: Vec<String> = self
             .config
@@ -44,24 +71,147 @@ impl Client {
             .map(|s| s.to_ascii_lowercase())
             .collect();
 
-        let game_names = games
+        let mut results = ranked_results(games, &simple_name, denylisted_name_substrings);
+        if let Some(region) = region {
+            prefer_region(&mut results, region);
+        }
+        Ok(results)
+    }
+
+    // searches for all names in a few requests, so that search hits the cache
+    pub fn prefetch(&self, queries: &[(String, Platform)]) -> Result<()> {
+        let mut bodies: Vec<String> = queries
+            .iter()
+            .filter_map(|(name, platform)| {
+                let platform_id: PlatformId = (*platform).try_into().ok()?;
+                Some(search_query(&simple_name(name), platform_id))
+            })
+            .collect();
+        bodies.sort();
+        bodies.dedup();
+        let games_uri = self.games_uri();
+        bodies.retain(|body| self.cache.get(&games_uri, body).is_none());
+        if self.config.cli.offline {
+            return Ok(());
+        }
+
+        let uri = format!("{}/v4/multiquery", self.igdb_config().api_base_url);
+
+        for chunk in bodies.chunks(MULTIQUERY_SIZE) {
+            let body: String = chunk
+                .iter()
+                .enumerate()
+                .map(|(i, query)| format!("query games \"{}\" {{\n{}\n}};\n", i, query))
+                .collect();
+
+            let request = self.http.post(&uri).body(body);
+            let response =
+                http::send(request, Some(&self.limiter), self.igdb_config().max_retries)?;
+            let results: Vec<MultiqueryResult> = json_from_str(&response.text()?)?;
+
+            let mut prefetched = self
+                .prefetched
+                .lock()
+                .map_err(|_| anyhow!("poisoned prefetch cache"))?;
+            for result in results {
+                let query = result.name.parse::<usize>().ok().and_then(|i| chunk.get(i));
+                if let Some(query) = query {
+                    let text = serde_json::to_string(&result.result)?;
+                    self.cache.set(&games_uri, query, &text)?;
+                    prefetched.insert(query.clone(), result.result);
+                }
+            }
+
+            info!(msg = "igdb searches prefetched", count = chunk.len());
+        }
+
+        Ok(())
+    }
+
+    pub fn get_game_by_id(&self, id: u32) -> Result<Game> {
+        let body = format!(
+            r#"fields {};
+    where id = {};"#,
+            FIELDS, id,
+        );
+
+        let games = self.query_games(body)?;
+        let game = games
+            .into_iter()
+            .find(|game| game.id == id)
+            .with_context(|| format!("no game with id {}", id))?;
+
+        info!(msg = "igdb game pinned", id, choice = &game.name);
+        Ok(game)
+    }
+
+    fn games_uri(&self) -> String {
+        format!("{}{}", self.igdb_config().api_base_url, GAMES_ENDPOINT)
+    }
+
+    fn query_games(&self, body: String) -> Result<Vec<Game>> {
+        let uri = self.games_uri();
+        if let Some(text) = self.cache.get(&uri, &body) {
+            return json_from_str(&text);
+        }
+        http::ensure_online(&self.config)?;
+
+        let request = self.http.post(&uri).body(body.clone());
+        let response = http::send(request, Some(&self.limiter), self.igdb_config().max_retries)?;
+        let text = response.text()?;
+
+        let games = json_from_str(&text)?;
+        self.cache.set(&uri, &body, &text)?;
+        Ok(games)
+    }
+
+    pub fn get_game(
+        &self,
+        name: &str,
+        platform: Platform,
+        region: Option<&str>,
+    ) -> Result<(Game, provider::Match)> {
+        let simple_name = simple_name(name);
+        let results = self.search(name, platform, region)?;
+
+        let game_names = results
             .iter()
-            .map(|game| game.name.to_owned())
+            .map(|(game, _)| game.name.to_owned())
             .collect::<Vec<_>>()
             .join(", ");
 
-        let res = best_result(games, &simple_name, denylisted_name_substrings)
-            .with_context(|| format!("no valid game for body {}", body));
+        let res = results
+            .into_iter()
+            .next()
+            .with_context(|| format!("no valid game for {}", simple_name));
+
+        let min_confidence = self.igdb_config().min_confidence;
 
         match res {
-            Ok(game) => {
+            Ok((game, m)) if m.confidence < min_confidence => {
+                info!(
+                    msg = "igdb game unmatched",
+                    query = simple_name,
+                    game_names = game_names,
+                    choice = &game.name,
+                    confidence = m.confidence,
+                );
+                Err(anyhow!(
+                    "unmatched: {} has confidence {:.2}",
+                    game.name,
+                    m.confidence
+                ))
+            }
+            Ok((game, m)) => {
                 info!(
                     msg = "igdb game found",
                     query = simple_name,
                     game_names = game_names,
                     choice = &game.name,
+                    alias = m.alias.as_deref().unwrap_or_default(),
+                    confidence = m.confidence,
                 );
-                Ok(game)
+                Ok((game, m))
             }
             Err(e) => {
                 error!(
@@ -74,24 +224,28 @@ impl Client {
         }
     }
 
-    pub fn download(
+    pub fn download_image(
         &self,
-        image: &Image,
+        image_id: &str,
         size: ImageSize,
         is_retina: bool,
         path: &Path,
     ) -> Result<()> {
+        http::ensure_online(&self.config)?;
+
         let uri = format!(
             "{}/igdb/image/upload/t_{}{}/{}.jpg",
-            self.config.yaml.igdb.images_base_url,
+            self.igdb_config().images_base_url,
             size,
             if is_retina { "_2x" } else { "" },
-            image.image_id
+            image_id
         );
 
         trace!(uri = &uri, path = path.to_string());
 
-        let response = self.http.get(uri).send()?;
+        // images are served by a CDN, without the API rate limit
+        let request = self.http.get(uri);
+        let response = http::send(request, None, self.igdb_config().max_retries)?;
 
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
@@ -105,82 +259,99 @@ impl Client {
     }
 }
 
-fn best_result(
-    games: Vec<Game>,
-    simple_name: &str,
-    denylisted_name_substrings: Vec<String>,
-) -> Option<Game> {
-    if games.is_empty() {
-        return None;
+impl MetadataProvider for Client {
+    fn get_metadata(
+        &self,
+        name: &str,
+        platform: Platform,
+        region: Option<&str>,
+    ) -> Result<provider::Game> {
+        let (game, m) = self.get_game(name, platform, region)?;
+        Ok(game.into_matched(m))
     }
 
-    let mut scores = vec![127; games.len()];
-
-    let version = get_number(simple_name).unwrap_or(1);
-
-    for (i, game) in games.iter().enumerate() {
-        scores[i] -= 12 * (i as u8);
-
-        let game_name = game.name.to_ascii_lowercase();
-        let this_version = get_number(&game_name).unwrap_or(1);
-
-        for denylisted_name_substring in &denylisted_name_substrings {
-            if game_name.contains(denylisted_name_substring) {
-                info!(
-                    status = "denylisted",
-                    game = &game_name,
-                    denylist_substr = &denylisted_name_substring
-                );
-                scores[i] /= 3;
-            }
-        }
-
-        if version != this_version {
-            scores[i] /= 2;
-        }
+    fn download(&self, image: &provider::Image, kind: ImageKind, path: &Path) -> Result<()> {
+        let size = match kind {
+            ImageKind::BoxFront => ImageSize::CoverBig,
+            ImageKind::Background | ImageKind::Screenshot => ImageSize::ScreenshotBig,
+        };
+        self.download_image(&image.id, size, true, path)
     }
+}
 
-    let mut max_score: u8 = 0;
-    let mut max_index = 0;
-    for (i, &score) in scores.iter().enumerate() {
-        if score > max_score {
-            max_score = score;
-            max_index = i;
-        }
-    }
+fn search_query(simple_name: &str, platform_id: PlatformId) -> String {
+    format!(
+        r#"fields {};
+    search "{}";
+    where platforms = ({});
+    limit 4;"#,
+        FIELDS,
+        simple_name.trim(),
+        platform_id.query_str(),
+    )
+}
 
-    Some(games.get(max_index).expect("invalid game index").to_owned())
+fn simple_name(name: &str) -> String {
+    name.replace('é', "e").replace(':', " ").replace(" - ", " ")
 }
 
-fn get_number(text: &str) -> Option<u8> {
-    for split in text.split_whitespace() {
-        if let Ok(n) = split.parse::<u8>() {
-            return Some(n);
-        }
-    }
-    None
+fn ranked_results(
+    games: Vec<Game>,
+    simple_name: &str,
+    denylisted_name_substrings: Vec<String>,
+) -> Vec<(Game, provider::Match)> {
+    let names: Vec<Vec<&str>> = games.iter().map(|game| game.names()).collect();
+    let matches = provider::ranked_matches(&names, simple_name, &denylisted_name_substrings);
+    let mut games: Vec<Option<Game>> = games.into_iter().map(Some).collect();
+    matches
+        .into_iter()
+        .filter_map(|m| Some((games.get_mut(m.index)?.take()?, m)))
+        .collect()
+}
+
+// stable, so that the rest keeps its order
+fn prefer_region(results: &mut [(Game, provider::Match)], region: &str) {
+    let best = match results.first() {
+        Some((_, m)) => m.confidence,
+        None => return,
+    };
+    results
+        .sort_by_key(|(game, m)| best - m.confidence > REGION_MARGIN || !game.released_in(region));
 }
 
 impl Client {
-    pub fn new(config: Arc<Config>, twitch: &twitch::Client) -> Result<Self> {
+    // without twitch, when offline
+    pub fn new(
+        config: Arc<Config>,
+        twitch: Option<&twitch::Client>,
+        cache: Arc<Cache>,
+    ) -> Result<Self> {
         trace!("init");
 
         let client_id = config.yaml.twitch.client_id.clone();
 
-        let access_token = &twitch.access_token;
-        let bearer = format!("Bearer {}", access_token);
-
         let mut headers = header::HeaderMap::new();
         headers.insert("Client-ID", header::HeaderValue::from_str(&client_id)?);
-        headers.insert("Authorization", header::HeaderValue::from_str(&bearer)?);
-        info!(bearer = &bearer);
+        if let Some(twitch) = twitch {
+            let bearer = format!("Bearer {}", twitch.access_token);
+            headers.insert("Authorization", header::HeaderValue::from_str(&bearer)?);
+            info!(bearer = &bearer);
+        }
 
         let http = HttpClient::builder()
             .default_headers(headers)
             .build()
             .expect("unable to init http client");
 
-        Ok(Self { config, http })
+        let limiter = RateLimiter::new(config.yaml.igdb.requests_per_second);
+
+        Ok(Self {
+            config,
+            http,
+            limiter,
+            cache,
+            prefetched: Mutex::new(HashMap::new()),
+        })
     }
 }
 
@@ -203,7 +374,7 @@ mod tests {
     }
 
     #[test]
-    fn test_best_result() {
+    fn test_ranked_results() {
         let cases = vec![
             (
                 vec![
@@ -229,7 +400,7 @@ mod tests {
                 vec!["dlroW oiraM repuS", "Super Mario World"],
                 "Super Mario World",
                 vec![""],
-                0,
+                1,
             ),
             (
                 vec!["dlroW oiraM repuS", "Super Mario World"],
@@ -237,6 +408,26 @@ mod tests {
                 vec!["dlroW"],
                 1,
             ),
+            (
+                vec![
+                    "Final Fantasy VI",
+                    "Final Fantasy VII",
+                    "Final Fantasy VIII",
+                ],
+                "Final Fantasy 7",
+                vec![""],
+                1,
+            ),
+            (
+                vec![
+                    "Metroid Prime Hunters",
+                    "Metroid Prime: Trilogy",
+                    "Metroid Prime",
+                ],
+                "Metroid Prime",
+                vec![""],
+                2,
+            ),
         ];
 
         for (game_names, simple_name, denylist, expected_index) in cases {
@@ -246,8 +437,51 @@ mod tests {
                 .into_iter()
                 .map(|s| s.to_ascii_lowercase())
                 .collect();
-            let choice = best_result(games, simple_name, denylist);
-            assert_eq!(expected, choice.unwrap().name)
+            let choice = ranked_results(games, simple_name, denylist);
+            assert_eq!(expected, choice[0].0.name)
         }
     }
+
+    #[test]
+    fn test_ranked_results_alias() {
+        let mut games = gen_games(vec!["Pokemon Gold Version", "Pokemon Crystal Version"]);
+        games[1].alternative_names = Some(vec![AlternativeName {
+            name: "Pocket Monsters Crystal".to_owned(),
+        }]);
+        games[1].game_localizations = Some(vec![Localization {
+            name: "Pokémon Version Cristal".to_owned(),
+        }]);
+
+        let (game, m) = ranked_results(games, "Pokémon - Version Cristal", vec![]).remove(0);
+        assert_eq!(game.name, "Pokemon Crystal Version");
+        assert_eq!(m.confidence, 1.0);
+        assert_eq!(m.alias.as_deref(), Some("Pokémon Version Cristal"));
+    }
+
+    #[test]
+    fn test_prefer_region() {
+        // e.g. separate entries for the releases of each region
+        let mut games = gen_games(vec![
+            "Final Fantasy VII",
+            "Final Fantasy VII",
+            "Final Fantasy Tactics",
+        ]);
+        let release = |region| {
+            Some(vec![ReleaseDate {
+                region: Some(region),
+            }])
+        };
+        games[0].release_dates = release(2);
+        games[1].release_dates = release(5);
+        games[2].release_dates = release(5);
+
+        let mut results = ranked_results(games, "Final Fantasy VII", vec![]);
+        let ids = |results: &[(Game, provider::Match)]| -> Vec<u32> {
+            results.iter().map(|(g, _)| g.id).collect()
+        };
+        assert_eq!(ids(&results), vec![0, 1, 2]);
+
+        prefer_region(&mut results, "Japan");
+        assert_eq!(ids(&results), vec![1, 0, 2]);
+    }
 }
@@ -1,5 +1,8 @@
+use crate::provider;
+use chrono::{Datelike, NaiveDateTime};
 use serde::Deserialize;
 use serde::Serialize;
+use std::cmp::max;
 use std::fmt;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -14,6 +17,26 @@ pub struct Genre {
     pub name: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AlternativeName {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Localization {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReleaseDate {
+    pub region: Option<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GamePlatform {
+    pub name: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Company {
     pub name: String,
@@ -47,6 +70,149 @@ pub struct Game {
     pub storyline: Option<String>,
     pub summary: Option<String>,
     pub multiplayer_modes: Option<Vec<MultiplayerMode>>,
+    pub alternative_names: Option<Vec<AlternativeName>>,
+    pub game_localizations: Option<Vec<Localization>>,
+    pub platforms: Option<Vec<GamePlatform>>,
+    pub release_dates: Option<Vec<ReleaseDate>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MultiqueryResult {
+    pub name: String,
+    pub result: Vec<Game>,
+}
+
+impl Game {
+    // the main name first, followed by alternative and localized names
+    pub fn names(&self) -> Vec<&str> {
+        let alternative_names = self.alternative_names.iter().flatten().map(|a| &a.name);
+        let localizations = self.game_localizations.iter().flatten().map(|l| &l.name);
+
+        std::iter::once(&self.name)
+            .chain(alternative_names)
+            .chain(localizations)
+            .map(|name| name.as_str())
+            .collect()
+    }
+
+    // region as in ROM headers, e.g. "Japan"
+    pub fn released_in(&self, region: &str) -> bool {
+        // IGDB region ids
+        const WORLDWIDE: u8 = 8;
+        let id = match region {
+            "Europe" => 1,
+            "USA" => 2,
+            "Japan" => 5,
+            "Asia" => 7,
+            "Korea" => 9,
+            _ => return false,
+        };
+        self.release_dates
+            .iter()
+            .flatten()
+            .any(|r| r.region == Some(id) || r.region == Some(WORLDWIDE))
+    }
+
+    pub fn into_matched(self, m: provider::Match) -> provider::Game {
+        provider::Game {
+            confidence: Some(m.confidence),
+            alias: m.alias,
+            ..self.into()
+        }
+    }
+
+    // "Pokemon Crystal Version (2000) [Game Boy Color] Game Freak, Nintendo"
+    pub fn describe(&self) -> String {
+        let mut text = self.name.clone();
+
+        let year = self
+            .first_release_date
+            .and_then(|secs| NaiveDateTime::from_timestamp_opt(secs as i64, 0))
+            .map(|date| date.year());
+        if let Some(year) = year {
+            text.push_str(&format!(" ({})", year));
+        }
+
+        let platforms: Vec<&str> = self
+            .platforms
+            .iter()
+            .flatten()
+            .map(|p| p.name.as_str())
+            .collect();
+        if !platforms.is_empty() {
+            text.push_str(&format!(" [{}]", platforms.join(", ")));
+        }
+
+        let companies: Vec<&str> = self
+            .involved_companies
+            .iter()
+            .flatten()
+            .map(|c| c.company.name.as_str())
+            .collect();
+        if !companies.is_empty() {
+            text.push(' ');
+            text.push_str(&companies.join(", "));
+        }
+
+        text
+    }
+}
+
+impl From<Game> for provider::Game {
+    fn from(game: Game) -> Self {
+        let companies = game.involved_companies.unwrap_or_default();
+
+        let developers = companies
+            .iter()
+            .filter(|x| x.developer.unwrap_or(false))
+            .map(|x| x.company.name.clone())
+            .collect();
+
+        let publishers = companies
+            .iter()
+            .filter(|x| x.publisher.unwrap_or(false))
+            .map(|x| x.company.name.clone())
+            .collect();
+
+        let players = game.multiplayer_modes.and_then(|modes| {
+            modes
+                .iter()
+                .map(|mode| {
+                    max(
+                        mode.offlinemax.unwrap_or(1),
+                        mode.offlinecoopmax.unwrap_or(1),
+                    )
+                })
+                .max()
+        });
+
+        let image = |image: &Image| provider::Image {
+            id: image.image_id.clone(),
+        };
+
+        Self {
+            id: game.id.to_string(),
+            name: game.name,
+            release_date: game.first_release_date.map(|secs| secs as i64),
+            genres: game
+                .genres
+                .unwrap_or_default()
+                .into_iter()
+                .map(|g| g.name)
+                .collect(),
+            developers,
+            publishers,
+            rating: game.aggregated_rating.or(game.rating),
+            players,
+            summary: game.summary,
+            description: game.storyline,
+            box_front: game.cover.as_ref().map(image),
+            background: game.artworks.as_ref().and_then(|x| x.first()).map(image),
+            screenshot: game.screenshots.as_ref().and_then(|x| x.first()).map(image),
+            confidence: None,
+            alias: None,
+        }
+    }
 }
 
 #[allow(dead_code)]
@@ -0,0 +1,58 @@
+use crate::collection::Collection;
+use crate::meta::Meta;
+use crate::prelude::*;
+use crate::xml::escape;
+use chrono::prelude::*;
+use std::fmt::Write as _;
+
+pub fn to_str(meta: &Meta, collection: &Collection) -> Result<String> {
+    let mut buf = String::new();
+    let game = &meta.game;
+    let rom_path = collection.path.join(&meta.file);
+
+    let mut field =
+        |name: &str, value: &str| writeln!(buf, "    <{}>{}</{}>", name, escape(value), name);
+
+    field("ID", &super::id(&rom_path)?)?;
+    field("Title", &game.name)?;
+    field("ApplicationPath", &rom_path.to_string())?;
+    field("Platform", &collection.launchbox_platform())?;
+
+    if let Some(secs) = game.release_date {
+        let naive = NaiveDateTime::from_timestamp_opt(secs, 0).context("invalid date")?;
+        field(
+            "ReleaseDate",
+            &naive.format("%Y-%m-%dT%H:%M:%S+00:00").to_string(),
+        )?;
+    }
+
+    if !game.developers.is_empty() {
+        field("Developer", &game.developers.join(", "))?;
+    }
+
+    if !game.publishers.is_empty() {
+        field("Publisher", &game.publishers.join(", "))?;
+    }
+
+    if !game.genres.is_empty() {
+        field("Genre", &game.genres.join("; "))?;
+    }
+
+    if let Some(x) = game.summary.as_ref().or(game.description.as_ref()) {
+        field("Notes", x)?;
+    }
+
+    // 0-5 stars
+    if let Some(x) = game.rating {
+        field(
+            "CommunityStarRating",
+            &format!("{:.1}", (x / 20.0).clamp(0.0, 5.0)),
+        )?;
+    }
+
+    if let Some(x) = game.players {
+        field("MaxPlayers", &x.to_string())?;
+    }
+
+    Ok(format!("  <Game>\n{}  </Game>\n", buf))
+}
@@ -0,0 +1,51 @@
+pub mod game;
+pub mod platform;
+
+use crate::collection::Collection;
+use crate::hash;
+use crate::prelude::*;
+
+impl Collection {
+    pub fn launchbox_platform(&self) -> String {
+        match platform::name(&self.platform) {
+            Some(name) => name.to_owned(),
+            None => self.name.clone(),
+        }
+    }
+}
+
+// LaunchBox replaces the characters Windows doesn't allow in filenames, and apostrophes
+pub fn sanitize(title: &str) -> String {
+    title.replace(['\\', '/', ':', '*', '?', '"', '<', '>', '|', '\''], "_")
+}
+
+// a GUID derived from the ROM path, so that ids survive regeneration
+pub fn id(rom_path: &Path) -> Result<String> {
+    let sha1 = hash::from_reader(rom_path.to_string().as_bytes())?.sha1;
+    Ok(format!(
+        "{}-{}-{}-{}-{}",
+        &sha1[0..8],
+        &sha1[8..12],
+        &sha1[12..16],
+        &sha1[16..20],
+        &sha1[20..32]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize() {
+        assert_eq!(sanitize("Kirby's Dream Land: 2"), "Kirby_s Dream Land_ 2");
+    }
+
+    #[test]
+    fn test_id() {
+        let id = id(Path::new("/ROMs/gba/Doom.zip")).unwrap();
+        assert_eq!(id.len(), 36);
+        assert_eq!(id, super::id(Path::new("/ROMs/gba/Doom.zip")).unwrap());
+        assert_ne!(id, super::id(Path::new("/ROMs/gba/Doom II.zip")).unwrap());
+    }
+}
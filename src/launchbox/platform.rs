@@ -0,0 +1,39 @@
+use crate::platform::Platform;
+
+// LaunchBox's platform names, which its scrapers and themes expect
+pub fn name(platform: &Platform) -> Option<&'static str> {
+    use Platform::*;
+    match platform {
+        Android => Some("Android"),
+        Arcade => Some("Arcade"),
+        Dos => Some("MS-DOS"),
+        Dreamcast => Some("Sega Dreamcast"),
+        GameBoy => Some("Nintendo Game Boy"),
+        GameBoyAdvance => Some("Nintendo Game Boy Advance"),
+        GameBoyColor => Some("Nintendo Game Boy Color"),
+        GameCube => Some("Nintendo GameCube"),
+        Linux => Some("Linux"),
+        Mac => Some("Apple Mac OS"),
+        MegaDrive => Some("Sega Genesis"),
+        Nes => Some("Nintendo Entertainment System"),
+        Nintendo3DS => Some("Nintendo 3DS"),
+        Nintendo64 => Some("Nintendo 64"),
+        NintendoDS => Some("Nintendo DS"),
+        NintendoDsi => Some("Nintendo DSi"),
+        PlayStation => Some("Sony Playstation"),
+        PlayStation2 => Some("Sony Playstation 2"),
+        PlayStation3 => Some("Sony Playstation 3"),
+        PlayStation4 => Some("Sony Playstation 4"),
+        Playstation5 => Some("Sony Playstation 5"),
+        Psp => Some("Sony PSP"),
+        SuperNintendo => Some("Super Nintendo Entertainment System"),
+        Switch => Some("Nintendo Switch"),
+        Wii => Some("Nintendo Wii"),
+        WiiU => Some("Nintendo Wii U"),
+        WiiWare => Some("Nintendo WiiWare"),
+        Windows => Some("Windows"),
+        Xbox => Some("Microsoft Xbox"),
+        Xbox360 => Some("Microsoft Xbox 360"),
+        _ => None,
+    }
+}
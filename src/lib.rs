@@ -1,17 +1,34 @@
 extern crate dns_common;
 
+mod archive;
+mod attract;
+mod cache;
 mod collection;
 mod commands;
 mod components;
 mod config;
+mod dat;
+mod descriptor;
+mod disc;
+mod es;
+mod export;
+mod hash;
+mod header;
+mod http;
 mod igdb;
+mod launchbox;
 mod meta;
 mod pegasus;
 mod platform;
 pub mod prelude;
+mod prompt;
+mod provider;
+mod retroarch;
+mod tgdb;
 mod title;
 mod twitch;
 mod worker;
+mod xml;
 
 use dns_common::tracing;
 use prelude::*;
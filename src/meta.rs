@@ -1,7 +1,12 @@
 use crate::collection::Collection;
+use crate::dat;
+use crate::hash;
+use crate::header;
 use crate::igdb;
 use crate::meta;
 use crate::prelude::*;
+use crate::provider;
+use crate::title;
 use dns_common::deser::yaml_from_path;
 use walkdir::WalkDir;
 
@@ -11,7 +16,34 @@ pub static YAML_NAME: &str = "meta.yaml";
 #[derive(Serialize, Deserialize)]
 pub struct Meta {
     pub file: PathBuf,
-    pub igdb: igdb::Game,
+    #[serde(default)]
+    pub provider: provider::Kind,
+    pub game: provider::Game,
+    pub hashes: Option<hash::Hashes>,
+    pub dat: Option<dat::Entry>,
+    pub discs: Option<Vec<PathBuf>>,
+    pub header: Option<header::Header>,
+    pub title: Option<title::Title>,
+    pub pin: Option<String>, // provider game id, picked by hand
+}
+
+// matches below this are worth a second look
+pub const CONFIDENCE_THRESHOLD: f32 = 0.8;
+
+impl Meta {
+    // games picked by hand are trusted, and so are the ones without a confidence,
+    // which weren't matched by name, e.g. by hash against a DAT
+    pub fn is_confident(&self, threshold: Option<f32>) -> bool {
+        let threshold = threshold.unwrap_or(CONFIDENCE_THRESHOLD);
+        self.pin.is_some() || self.game.confidence.unwrap_or(1.0) >= threshold
+    }
+}
+
+// meta files written before providers were pluggable
+#[derive(Deserialize)]
+struct LegacyMeta {
+    file: PathBuf,
+    igdb: igdb::Game,
 }
 
 pub fn get_filepaths(collections: &[Collection]) -> Result<HashMap<usize, Vec<PathBuf>>> {
@@ -49,6 +81,29 @@ pub fn get_filepaths(collections: &[Collection]) -> Result<HashMap<usize, Vec<Pa
     Ok(filepaths_map)
 }
 
+// where media downloads the image of this kind
+pub fn image_path(meta_path: &Path, kind: provider::ImageKind) -> PathBuf {
+    let path = meta_path.to_string().replace(YAML_NAME, kind.filename());
+    PathBuf::from(path)
+}
+
 pub fn get(path: &Path) -> Result<Meta> {
-    yaml_from_path(path)
+    let res: Result<Meta> = yaml_from_path(path);
+    match res {
+        Ok(meta) => Ok(meta),
+        Err(e) => {
+            let legacy: LegacyMeta = yaml_from_path(path).map_err(|_| e)?;
+            Ok(Meta {
+                file: legacy.file,
+                provider: provider::Kind::Igdb,
+                game: legacy.igdb.into(),
+                hashes: None,
+                dat: None,
+                discs: None,
+                header: None,
+                title: None,
+                pin: None,
+            })
+        }
+    }
 }
@@ -1,8 +1,8 @@
 use crate::collection::Collection;
+use crate::disc;
 use crate::meta::{self, Meta};
 use crate::prelude::*;
 use chrono::prelude::*;
-use std::cmp::max;
 use std::fmt::Write as _;
 
 pub fn to_str(meta: Meta, meta_file: &Path, _collection: &Collection) -> Result<String> {
@@ -13,77 +13,53 @@ pub fn to_str(meta: Meta, meta_file: &Path, _collection: &Collection) -> Result<
         .to_string_lossy()
         .replace(meta::YAML_NAME, "");
 
-    writeln!(buf, "game: {}", &meta.igdb.name)?;
+    let game = meta.game;
 
-    writeln!(buf, "file: ../{}", &meta.file.to_string())?;
+    writeln!(buf, "game: {}", &game.name)?;
 
-    if let Some(secs) = meta.igdb.first_release_date {
-        let naive = NaiveDateTime::from_timestamp_opt(secs as i64, 0).context("invalid date")?;
+    match &meta.discs {
+        Some(discs) if !disc::is_playlist(&meta.file) => {
+            writeln!(buf, "files:")?;
+            for d in discs {
+                writeln!(buf, "  ../{}", d.to_string())?;
+            }
+        }
+        _ => writeln!(buf, "file: ../{}", &meta.file.to_string())?,
+    };
+
+    if let Some(secs) = game.release_date {
+        let naive = NaiveDateTime::from_timestamp_opt(secs, 0).context("invalid date")?;
         let datetime: DateTime<Utc> = DateTime::from_utc(naive, Utc);
         writeln!(buf, "release: {}", datetime.format("%Y-%m-%d"))?
     };
 
-    if let Some(x) = meta.igdb.genres {
-        let genres: Vec<_> = x.iter().map(|g| g.name.clone()).collect();
-        if !genres.is_empty() {
-            writeln!(buf, "genres: {}", genres.join(", "))?
-        }
+    if !game.genres.is_empty() {
+        writeln!(buf, "genres: {}", game.genres.join(", "))?
     };
 
     writeln!(buf, "assets.boxFront: {}boxFront.jpg", rom)?;
     writeln!(buf, "assets.background: {}background.jpg", rom)?;
     writeln!(buf, "assets.screenshot: {}screenshot.jpg", rom)?;
 
-    if let Some(x) = meta.igdb.aggregated_rating.or(meta.igdb.rating) {
+    if let Some(x) = game.rating {
         writeln!(buf, "rating: {}%", x as u8)?;
     };
 
-    if let Some(companies) = &meta.igdb.involved_companies {
-        let devs: Vec<String> = companies
-            .iter()
-            .filter(|x| x.developer.unwrap_or(false))
-            .map(|x| x.company.name.clone())
-            .collect();
-
-        if !devs.is_empty() {
-            writeln!(buf, "developers: {}", devs.join(", "))?
-        }
+    if !game.developers.is_empty() {
+        writeln!(buf, "developers: {}", game.developers.join(", "))?
     };
 
-    if let Some(companies) = meta.igdb.involved_companies {
-        let publishers: Vec<String> = companies
-            .into_iter()
-            .filter(|x| x.publisher.unwrap_or(false))
-            .map(|x| x.company.name)
-            .collect();
-
-        if !publishers.is_empty() {
-            writeln!(buf, "publishers: {}", publishers.join(", "))?
-        }
+    if !game.publishers.is_empty() {
+        writeln!(buf, "publishers: {}", game.publishers.join(", "))?
     };
 
-    writeln!(
-        buf,
-        "players: {}",
-        meta.igdb
-            .multiplayer_modes
-            .unwrap_or_default()
-            .iter()
-            .map(|mode| {
-                max(
-                    mode.offlinemax.unwrap_or(1),
-                    mode.offlinecoopmax.unwrap_or(1),
-                )
-            })
-            .max()
-            .unwrap_or(1)
-    )?;
+    writeln!(buf, "players: {}", game.players.unwrap_or(1))?;
 
-    if let Some(x) = meta.igdb.summary {
+    if let Some(x) = game.summary {
         writeln!(buf, "summary: {}", x.replace('\n', "\\n"))?
     };
 
-    if let Some(x) = meta.igdb.storyline {
+    if let Some(x) = game.description {
         writeln!(buf, "description: {}", x.replace('\n', "\\n"))?
     };
 
@@ -0,0 +1,64 @@
+use crate::prelude::*;
+use std::io::{self, BufRead, Write};
+use std::sync::Mutex;
+
+// workers run in parallel, but only one of them may ask at a time
+static LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Debug, PartialEq)]
+pub enum Choice {
+    Pick(usize),
+    Search(String),
+    Skip,
+}
+
+pub fn choose(query: &str, options: &[String]) -> Result<Choice> {
+    let _lock = LOCK.lock().map_err(|_| anyhow!("prompt lock poisoned"))?;
+
+    // logs are written to stdout too, so holding it until the answer is read
+    // keeps the other workers' logs out of the prompt
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+
+    writeln!(out, "\n{}", query)?;
+    if options.is_empty() {
+        writeln!(out, "  no results")?;
+    }
+    for (i, option) in options.iter().enumerate() {
+        writeln!(out, "  {}) {}", i + 1, option)?;
+    }
+    write!(out, "number, new search or empty to skip: ")?;
+    out.flush()?;
+
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    drop(out);
+
+    Ok(parse(&line, options.len()))
+}
+
+// numbers out of range are searched for, e.g. "1942"
+fn parse(input: &str, n_options: usize) -> Choice {
+    let input = input.trim();
+    match input.parse::<usize>() {
+        _ if input.is_empty() => Choice::Skip,
+        Ok(i) if (1..=n_options).contains(&i) => Choice::Pick(i - 1),
+        _ => Choice::Search(input.to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("\n", 3), Choice::Skip);
+        assert_eq!(parse(" 2\n", 3), Choice::Pick(1));
+        assert_eq!(parse("1942\n", 3), Choice::Search("1942".into()));
+        assert_eq!(
+            parse("Pokemon Crystal\n", 3),
+            Choice::Search("Pokemon Crystal".into())
+        );
+    }
+}
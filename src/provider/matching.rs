@@ -0,0 +1,184 @@
+use crate::prelude::*;
+use strsim::jaro_winkler;
+
+// without i, v and x, which are more often letters, as in Mega Man X
+static ROMAN_NUMERALS: [(&str, u8); 17] = [
+    ("ii", 2),
+    ("iii", 3),
+    ("iv", 4),
+    ("vi", 6),
+    ("vii", 7),
+    ("viii", 8),
+    ("ix", 9),
+    ("xi", 11),
+    ("xii", 12),
+    ("xiii", 13),
+    ("xiv", 14),
+    ("xv", 15),
+    ("xvi", 16),
+    ("xvii", 17),
+    ("xviii", 18),
+    ("xix", 19),
+    ("xx", 20),
+];
+
+static STOPWORDS: [&str; 3] = ["the", "a", "an"];
+
+// matching only the main title or only the subtitle is weaker evidence
+static SUBTITLE_FACTOR: f64 = 0.9;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    pub index: usize,
+    pub confidence: f32,       // 0-1
+    pub alias: Option<String>, // set if an alternative name matched best
+}
+
+// each result has its main name first, followed by its aliases
+pub fn best_match(
+    names: &[Vec<&str>],
+    simple_name: &str,
+    denylisted_name_substrings: &[String],
+) -> Option<Match> {
+    ranked_matches(names, simple_name, denylisted_name_substrings)
+        .into_iter()
+        .next()
+}
+
+// best first
+pub fn ranked_matches(
+    names: &[Vec<&str>],
+    simple_name: &str,
+    denylisted_name_substrings: &[String],
+) -> Vec<Match> {
+    let version = get_number(&tokens(simple_name)).unwrap_or(1);
+
+    let mut matches: Vec<(f32, Match)> = vec![];
+
+    for (i, aliases) in names.iter().enumerate() {
+        let name = match aliases.first() {
+            Some(name) => name,
+            None => continue,
+        };
+
+        let (alias_index, confidence) = aliases
+            .iter()
+            .map(|alias| {
+                let mut confidence = similarity(simple_name, alias);
+                let this_version = get_number(&tokens(alias)).unwrap_or(1);
+                if version != this_version {
+                    confidence /= 2.0;
+                }
+                confidence
+            })
+            .enumerate()
+            .fold((0, 0.0), |max, (j, c)| if c > max.1 { (j, c) } else { max });
+
+        // earlier results are more relevant
+        let mut score = confidence * (1.0 - 0.02 * i.min(10) as f32);
+
+        let game_name = name.to_ascii_lowercase();
+        for denylisted_name_substring in denylisted_name_substrings {
+            if game_name.contains(denylisted_name_substring) {
+                info!(
+                    status = "denylisted",
+                    game = &game_name,
+                    denylist_substr = &denylisted_name_substring
+                );
+                score /= 3.0;
+            }
+        }
+
+        let alias = Some(aliases[alias_index].to_owned()).filter(|_| alias_index > 0);
+        let m = Match {
+            index: i,
+            confidence,
+            alias,
+        };
+        matches.push((score, m));
+    }
+
+    // stable, so ties keep the original order
+    matches.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    matches.into_iter().map(|(_, m)| m).collect()
+}
+
+// 0-1, insensitive to case, punctuation, word order, articles and roman numerals
+pub fn similarity(a: &str, b: &str) -> f32 {
+    let full = token_similarity(&tokens(a), &tokens(b));
+
+    let partial = [
+        main_title(a).map(|main| token_similarity(&tokens(main), &tokens(b))),
+        main_title(b).map(|main| token_similarity(&tokens(a), &tokens(main))),
+    ]
+    .into_iter()
+    .flatten()
+    .fold(0.0, f64::max)
+        * SUBTITLE_FACTOR;
+
+    full.max(partial) as f32
+}
+
+// "Metroid Prime: Trilogy" -> "Metroid Prime"
+fn main_title(name: &str) -> Option<&str> {
+    let end = name.find(':').or_else(|| name.find(" - "))?;
+    Some(&name[..end])
+}
+
+fn token_similarity(a: &[String], b: &[String]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let a_set: HashSet<&String> = a.iter().collect();
+    let b_set: HashSet<&String> = b.iter().collect();
+    let common = a_set.intersection(&b_set).count();
+    let dice = 2.0 * common as f64 / (a_set.len() + b_set.len()) as f64;
+
+    dice.max(jaro_winkler(&a.join(" "), &b.join(" ")))
+}
+
+fn tokens(name: &str) -> Vec<String> {
+    name.to_lowercase()
+        .replace('&', " and ")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty() && !STOPWORDS.contains(t))
+        .map(|t| match ROMAN_NUMERALS.iter().find(|(r, _)| *r == t) {
+            Some((_, n)) => n.to_string(),
+            None => t.to_owned(),
+        })
+        .collect()
+}
+
+fn get_number(tokens: &[String]) -> Option<u8> {
+    tokens.iter().find_map(|t| t.parse::<u8>().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!(similarity("Final Fantasy 7", "FINAL FANTASY VII"), 1.0);
+        assert_eq!(
+            similarity(
+                "Legend of Zelda, The - Minish Cap",
+                "The Legend of Zelda: The Minish Cap"
+            ),
+            1.0
+        );
+        assert!(similarity("Metroid Prime", "Metroid Prime: Trilogy") >= 0.9);
+        assert!(similarity("Super Mario World", "Mortal Kombat") < 0.7);
+    }
+
+    #[test]
+    fn test_get_number() {
+        assert_eq!(get_number(&tokens("Final Fantasy VII")), Some(7));
+        assert_eq!(get_number(&tokens("Street Fighter II")), Some(2));
+        assert_eq!(get_number(&tokens("Mega Man X")), None);
+        assert_eq!(get_number(&tokens("Pokemon X")), None);
+        assert_eq!(get_number(&tokens("Mega Man X3")), None);
+        assert_eq!(get_number(&tokens("Mega Man 3")), Some(3));
+    }
+}
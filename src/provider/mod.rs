@@ -0,0 +1,35 @@
+mod matching;
+mod schema;
+
+pub use matching::*;
+pub use schema::*;
+
+use crate::dat;
+use crate::igdb;
+use crate::platform::Platform;
+use crate::prelude::*;
+use crate::tgdb;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    #[default]
+    Igdb,
+    Tgdb,
+    Dat,
+}
+
+pub trait MetadataProvider: Send + Sync {
+    // the region, e.g. of a disc serial, helps picking the right regional release
+    fn get_metadata(&self, name: &str, platform: Platform, region: Option<&str>) -> Result<Game>;
+    fn download(&self, image: &Image, kind: ImageKind, path: &Path) -> Result<()>;
+}
+
+pub fn get(system: &System, kind: Kind) -> Result<&dyn MetadataProvider> {
+    let provider: &dyn MetadataProvider = match kind {
+        Kind::Igdb => system.get::<igdb::Client>()?,
+        Kind::Tgdb => system.get::<tgdb::Client>()?,
+        Kind::Dat => system.get::<dat::Store>()?,
+    };
+    Ok(provider)
+}
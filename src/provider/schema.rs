@@ -0,0 +1,90 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Image {
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Game {
+    pub id: String,
+    pub name: String,
+    pub release_date: Option<i64>, // secs
+    #[serde(default)]
+    pub genres: Vec<String>,
+    #[serde(default)]
+    pub developers: Vec<String>,
+    #[serde(default)]
+    pub publishers: Vec<String>,
+    pub rating: Option<f32>, // 0-100
+    pub players: Option<u32>,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub box_front: Option<Image>,
+    pub background: Option<Image>,
+    pub screenshot: Option<Image>,
+    pub confidence: Option<f32>, // 0-1, how closely the name matched the query
+    pub alias: Option<String>,   // alternative name that matched the query
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum ImageKind {
+    BoxFront,
+    Background,
+    Screenshot,
+}
+
+impl ImageKind {
+    pub const ALL: [ImageKind; 3] = [
+        ImageKind::BoxFront,
+        ImageKind::Background,
+        ImageKind::Screenshot,
+    ];
+
+    pub fn filename(self) -> &'static str {
+        use ImageKind::*;
+        match self {
+            BoxFront => "boxFront.jpg",
+            Background => "background.jpg",
+            Screenshot => "screenshot.jpg",
+        }
+    }
+}
+
+impl Game {
+    // e.g. ["rating", "screenshot"]
+    pub fn changed_fields(&self, other: &Game) -> Vec<&'static str> {
+        let fields = [
+            ("id", self.id != other.id),
+            ("name", self.name != other.name),
+            ("release_date", self.release_date != other.release_date),
+            ("genres", self.genres != other.genres),
+            ("developers", self.developers != other.developers),
+            ("publishers", self.publishers != other.publishers),
+            ("rating", self.rating != other.rating),
+            ("players", self.players != other.players),
+            ("summary", self.summary != other.summary),
+            ("description", self.description != other.description),
+            ("box_front", self.box_front != other.box_front),
+            ("background", self.background != other.background),
+            ("screenshot", self.screenshot != other.screenshot),
+            ("confidence", self.confidence != other.confidence),
+            ("alias", self.alias != other.alias),
+        ];
+        fields
+            .into_iter()
+            .filter(|(_, changed)| *changed)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    pub fn image(&self, kind: ImageKind) -> Option<&Image> {
+        use ImageKind::*;
+        match kind {
+            BoxFront => self.box_front.as_ref(),
+            Background => self.background.as_ref(),
+            Screenshot => self.screenshot.as_ref(),
+        }
+    }
+}
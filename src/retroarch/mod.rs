@@ -0,0 +1,33 @@
+pub mod platform;
+pub mod playlist;
+pub mod thumbnail;
+
+use crate::prelude::*;
+
+static DETECT: &str = "DETECT";
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Core {
+    pub path: PathBuf,
+    pub name: String,
+}
+
+// the characters RetroArch replaces with _ in thumbnail filenames
+pub fn sanitize(label: &str) -> String {
+    label.replace(['&', '*', '/', ':', '`', '<', '>', '?', '\\', '|'], "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize() {
+        assert_eq!(
+            sanitize("Mario & Luigi: Superstar Saga"),
+            "Mario _ Luigi_ Superstar Saga"
+        );
+        assert_eq!(sanitize("A/B\\C|D?*<>`"), "A_B_C_D_____");
+    }
+}
@@ -0,0 +1,28 @@
+use crate::platform::Platform;
+
+// the database RetroArch scans this platform against
+pub fn db_name(platform: &Platform) -> Option<&'static str> {
+    use Platform::*;
+    match platform {
+        Dos => Some("DOS.rdb"),
+        Dreamcast => Some("Sega - Dreamcast.rdb"),
+        GameBoy => Some("Nintendo - Game Boy.rdb"),
+        GameBoyAdvance => Some("Nintendo - Game Boy Advance.rdb"),
+        GameBoyColor => Some("Nintendo - Game Boy Color.rdb"),
+        GameCube => Some("Nintendo - GameCube.rdb"),
+        MegaDrive => Some("Sega - Mega Drive - Genesis.rdb"),
+        Nes => Some("Nintendo - Nintendo Entertainment System.rdb"),
+        Nintendo3DS => Some("Nintendo - Nintendo 3DS.rdb"),
+        Nintendo64 => Some("Nintendo - Nintendo 64.rdb"),
+        NintendoDS => Some("Nintendo - Nintendo DS.rdb"),
+        NintendoDsi => Some("Nintendo - Nintendo DSi.rdb"),
+        PlayStation => Some("Sony - PlayStation.rdb"),
+        PlayStation2 => Some("Sony - PlayStation 2.rdb"),
+        PlayStation3 => Some("Sony - PlayStation 3.rdb"),
+        Psp => Some("Sony - PlayStation Portable.rdb"),
+        SuperNintendo => Some("Nintendo - Super Nintendo Entertainment System.rdb"),
+        Wii => Some("Nintendo - Wii.rdb"),
+        Xbox => Some("Microsoft - Xbox.rdb"),
+        _ => None,
+    }
+}
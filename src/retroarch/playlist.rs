@@ -0,0 +1,78 @@
+use super::{platform, Core, DETECT};
+use crate::collection::Collection;
+use crate::meta::Meta;
+use crate::prelude::*;
+
+static VERSION: &str = "1.5";
+
+#[derive(Serialize)]
+pub struct Playlist {
+    version: String,
+    default_core_path: String,
+    default_core_name: String,
+    label_display_mode: u8,
+    right_thumbnail_mode: u8,
+    left_thumbnail_mode: u8,
+    sort_mode: u8,
+    pub items: Vec<Item>,
+}
+
+#[derive(Serialize)]
+pub struct Item {
+    pub path: String,
+    pub label: String,
+    pub core_path: String,
+    pub core_name: String,
+    pub crc32: String,
+    pub db_name: String,
+}
+
+impl Playlist {
+    pub fn new(collection: &Collection) -> Self {
+        let (core_path, core_name) = core(collection);
+        Self {
+            version: VERSION.to_owned(),
+            default_core_path: core_path,
+            default_core_name: core_name,
+            label_display_mode: 0,
+            right_thumbnail_mode: 0,
+            left_thumbnail_mode: 0,
+            sort_mode: 0,
+            items: vec![],
+        }
+    }
+}
+
+// RetroArch looks thumbnails up by the database name, when there's one
+pub fn name(collection: &Collection) -> String {
+    match platform::db_name(&collection.platform) {
+        Some(db_name) => db_name.trim_end_matches(".rdb").to_owned(),
+        None => collection.name.clone(),
+    }
+}
+
+pub fn item(meta: &Meta, collection: &Collection) -> Item {
+    let (core_path, core_name) = core(collection);
+    let crc32 = match &meta.hashes {
+        Some(hashes) => format!("{}|crc", hashes.crc32.to_ascii_uppercase()),
+        None => DETECT.to_owned(),
+    };
+
+    Item {
+        path: collection.path.join(&meta.file).to_string(),
+        label: meta.game.name.clone(),
+        core_path,
+        core_name,
+        crc32,
+        db_name: platform::db_name(&collection.platform)
+            .unwrap_or_default()
+            .to_owned(),
+    }
+}
+
+fn core(collection: &Collection) -> (String, String) {
+    match &collection.retroarch_core {
+        Some(Core { path, name }) => (path.to_string(), name.clone()),
+        None => (DETECT.to_owned(), DETECT.to_owned()),
+    }
+}
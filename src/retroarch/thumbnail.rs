@@ -0,0 +1,82 @@
+use super::sanitize;
+use crate::export;
+use crate::meta;
+use crate::prelude::*;
+use crate::provider::ImageKind;
+use image::io::Reader as ImageReader;
+use image::ImageFormat;
+use std::fs;
+
+// louvre has no title screens, so screenshots stand in for them
+static DIRS: [(&str, ImageKind); 3] = [
+    ("Named_Boxarts", ImageKind::BoxFront),
+    ("Named_Snaps", ImageKind::Screenshot),
+    ("Named_Titles", ImageKind::Screenshot),
+];
+
+// RetroArch only looks thumbnails up as .png, and picks the decoder by extension
+static EXTENSION: &str = "png";
+
+// copies or links the downloaded images into thumbnails/<playlist>
+pub fn export(playlist_dir: &Path, meta_file: &Path, label: &str, symlink: bool) -> Result<()> {
+    for (dir, kind) in DIRS {
+        let image_path = meta::image_path(meta_file, kind);
+        if !image_path.exists() {
+            continue;
+        }
+
+        let target = playlist_dir
+            .join(dir)
+            .join(format!("{}.{}", sanitize(label), EXTENSION));
+
+        place_png(&image_path, &target, symlink)?;
+    }
+
+    Ok(())
+}
+
+// other formats are converted, so they can't be linked
+fn place_png(image_path: &Path, target: &Path, symlink: bool) -> Result<()> {
+    let reader = ImageReader::open(image_path)?.with_guessed_format()?;
+    if reader.format() == Some(ImageFormat::Png) {
+        return export::place(image_path, target, symlink);
+    }
+
+    let image = reader
+        .decode()
+        .with_context(|| format!("unable to decode {}", image_path.to_string()))?;
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if target.symlink_metadata().is_ok() {
+        fs::remove_file(target)?;
+    }
+    image.save_with_format(target, ImageFormat::Png)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn test_place_png() {
+        let dir = std::env::temp_dir().join("louvre-test_place_png");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let jpg = dir.join("boxFront.jpg");
+        RgbImage::from_pixel(4, 4, Rgb([0, 255, 0]))
+            .save_with_format(&jpg, ImageFormat::Jpeg)
+            .unwrap();
+
+        let png = dir.join("thumbnails").join("Game.png");
+        place_png(&jpg, &png, true).unwrap();
+
+        assert!(!png.symlink_metadata().unwrap().file_type().is_symlink());
+        assert!(fs::read(&png).unwrap().starts_with(b"\x89PNG"));
+    }
+}
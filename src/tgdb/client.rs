@@ -0,0 +1,274 @@
+use super::*;
+use crate::cache::Cache;
+use crate::config::yaml::Tgdb;
+use crate::dns_common::deser::json_from_str;
+use crate::http;
+use crate::platform::Platform;
+use crate::prelude::*;
+use crate::provider::{self, ImageKind, MetadataProvider};
+use chrono::NaiveDate;
+use once_cell::sync::OnceCell;
+use reqwest::blocking::Client as HttpClient;
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::io::Cursor;
+
+pub struct Client {
+    config: Arc<Config>,
+    http: HttpClient,
+    genres: OnceCell<HashMap<u32, String>>,
+    developers: OnceCell<HashMap<u32, String>>,
+    publishers: OnceCell<HashMap<u32, String>>,
+    cache: Arc<Cache>,
+}
+
+impl Client {
+    fn tgdb_config(&self) -> &Tgdb {
+        self.config
+            .yaml
+            .tgdb
+            .as_ref()
+            .expect("tgdb config checked on init")
+    }
+
+    fn get_json<T: DeserializeOwned>(&self, endpoint: &str, query: &[(&str, &str)]) -> Result<T> {
+        let tgdb_config = self.tgdb_config();
+        let uri = format!("{}{}", tgdb_config.api_base_url, endpoint);
+
+        // the api key isn't part of the cache key
+        let cache_key: Vec<String> = query.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        let cache_key = cache_key.join("&");
+        if let Some(text) = self.cache.get(&uri, &cache_key) {
+            return json_from_str(&text);
+        }
+        http::ensure_online(&self.config)?;
+
+        trace!(uri = &uri);
+
+        let response = self
+            .http
+            .get(&uri)
+            .query(&[("apikey", tgdb_config.api_key.as_str())])
+            .query(query)
+            .send()?
+            .error_for_status()?;
+        let text = response.text()?;
+
+        let value = json_from_str(&text)?;
+        self.cache.set(&uri, &cache_key, &text)?;
+        Ok(value)
+    }
+
+    pub fn get_game(&self, name: &str, platform: Platform) -> Result<(Game, Vec<Image>, f32)> {
+        let platform_id: PlatformId = platform.try_into()?;
+
+        let simple_name = name.replace(':', " ").replace(" - ", " ");
+        let platform_str = platform_id.query_str();
+
+        let response: GamesResponse = self.get_json(
+            "/v1.1/Games/ByGameName",
+            &[
+                ("name", simple_name.trim()),
+                ("fields", "players,publishers,genres,overview"),
+                ("filter[platform]", &platform_str),
+                ("include", "boxart"),
+            ],
+        )?;
+
+        let games = response.data.games;
+
+        let denylisted_name_substrings: Vec<String> = self
+            .tgdb_config()
+            .denylisted_name_substrings
+            .iter()
+            .map(|s| s.to_ascii_lowercase())
+            .collect();
+
+        let names: Vec<&str> = games.iter().map(|game| game.game_title.as_str()).collect();
+        let game_names = names.join(", ");
+
+        let candidates: Vec<Vec<&str>> = names.iter().map(|name| vec![*name]).collect();
+        let best = provider::best_match(&candidates, &simple_name, &denylisted_name_substrings);
+        let min_confidence = self.tgdb_config().min_confidence;
+
+        let (game, confidence) = match best.and_then(|m| Some((games.get(m.index)?, m.confidence)))
+        {
+            Some((game, confidence)) if confidence < min_confidence => {
+                info!(
+                    msg = "tgdb game unmatched",
+                    query = simple_name,
+                    game_names = game_names,
+                    choice = &game.game_title,
+                    confidence,
+                );
+                return Err(anyhow!(
+                    "unmatched: {} has confidence {:.2}",
+                    game.game_title,
+                    confidence
+                ));
+            }
+            Some((game, confidence)) => {
+                info!(
+                    msg = "tgdb game found",
+                    query = simple_name,
+                    game_names = game_names,
+                    choice = &game.game_title,
+                    confidence,
+                );
+                (game.to_owned(), confidence)
+            }
+            None => {
+                error!(
+                    msg = "tgdb game not found",
+                    query = simple_name,
+                    game_names = game_names,
+                );
+                return Err(anyhow!("no valid game for name {}", simple_name));
+            }
+        };
+
+        let id = game.id.to_string();
+
+        let mut images: Vec<Image> = response
+            .include
+            .and_then(|include| include.boxart)
+            .and_then(|mut boxart| boxart.data.remove(&id))
+            .unwrap_or_default();
+
+        let response: ImagesResponse = self.get_json(
+            "/v1/Games/Images",
+            &[("games_id", &id), ("filter[type]", "fanart,screenshot")],
+        )?;
+
+        let mut images_data = response.data.images;
+        images.append(&mut images_data.remove(&id).unwrap_or_default());
+
+        Ok((game, images, confidence))
+    }
+
+    fn names(
+        &self,
+        cell: &OnceCell<HashMap<u32, String>>,
+        endpoint: &str,
+        ids: Option<Vec<u32>>,
+    ) -> Result<Vec<String>> {
+        let ids = ids.unwrap_or_default();
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let lookup = cell.get_or_try_init(|| -> Result<HashMap<u32, String>> {
+            let response: LookupResponse = self.get_json(endpoint, &[])?;
+            Ok(response
+                .data
+                .entries
+                .into_values()
+                .map(|named| (named.id, named.name))
+                .collect())
+        })?;
+
+        Ok(ids
+            .iter()
+            .filter_map(|id| lookup.get(id).cloned())
+            .collect())
+    }
+
+    pub fn download_image(&self, filename: &str, size: &str, path: &Path) -> Result<()> {
+        http::ensure_online(&self.config)?;
+
+        let uri = format!(
+            "{}/{}/{}",
+            self.tgdb_config().images_base_url,
+            size,
+            filename
+        );
+
+        trace!(uri = &uri, path = path.to_string());
+
+        let response = self.http.get(uri).send()?.error_for_status()?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = File::create(path)?;
+        let mut content = Cursor::new(response.bytes()?);
+        std::io::copy(&mut content, &mut file)?;
+
+        Ok(())
+    }
+}
+
+impl MetadataProvider for Client {
+    fn get_metadata(
+        &self,
+        name: &str,
+        platform: Platform,
+        _region: Option<&str>,
+    ) -> Result<provider::Game> {
+        let (game, images, confidence) = self.get_game(name, platform)?;
+
+        let image = |kind: &str, side: Option<&str>| {
+            images
+                .iter()
+                .find(|image| image.kind == kind && image.side.as_deref() == side)
+                .map(|image| provider::Image {
+                    id: image.filename.clone(),
+                })
+        };
+
+        let release_date = game
+            .release_date
+            .as_ref()
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|datetime| datetime.timestamp());
+
+        Ok(provider::Game {
+            id: game.id.to_string(),
+            name: game.game_title,
+            release_date,
+            genres: self.names(&self.genres, "/v1/Genres", game.genres)?,
+            developers: self.names(&self.developers, "/v1/Developers", game.developers)?,
+            publishers: self.names(&self.publishers, "/v1/Publishers", game.publishers)?,
+            rating: None,
+            players: game.players,
+            summary: game.overview,
+            description: None,
+            box_front: image("boxart", Some("front")),
+            background: image("fanart", None),
+            screenshot: image("screenshot", None),
+            confidence: Some(confidence),
+            alias: None,
+        })
+    }
+
+    fn download(&self, image: &provider::Image, _kind: ImageKind, path: &Path) -> Result<()> {
+        self.download_image(&image.id, "original", path)
+    }
+}
+
+impl Client {
+    pub fn new(config: Arc<Config>, cache: Arc<Cache>) -> Result<Self> {
+        trace!("init");
+
+        if config.yaml.tgdb.is_none() {
+            return Err(anyhow!("missing tgdb config"));
+        }
+
+        let http = HttpClient::builder()
+            .build()
+            .expect("unable to init http client");
+
+        Ok(Self {
+            config,
+            http,
+            genres: OnceCell::new(),
+            developers: OnceCell::new(),
+            publishers: OnceCell::new(),
+            cache,
+        })
+    }
+}
+
+impl Component for Client {}
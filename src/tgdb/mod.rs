@@ -0,0 +1,7 @@
+mod client;
+mod platform;
+mod schema;
+
+pub use client::Client;
+pub use platform::PlatformId;
+pub use schema::*;
@@ -0,0 +1,62 @@
+use crate::platform::Platform;
+use crate::prelude::*;
+use derive_more::Display;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+#[derive(Display)]
+pub struct PlatformId(u16);
+
+impl PlatformId {
+    pub fn query_str(self) -> String {
+        if self.0 == 18 {
+            "18,36".into() // Genesis + Mega Drive
+        } else {
+            self.to_string()
+        }
+    }
+}
+
+static MAPPING: Lazy<HashMap<u16, Platform>> = Lazy::new(|| {
+    use Platform::*;
+    HashMap::from([
+        (1, Windows),
+        (2, GameCube),
+        (3, Nintendo64),
+        (4, GameBoy),
+        (5, GameBoyAdvance),
+        (6, SuperNintendo),
+        (7, Nes),
+        (8, NintendoDS),
+        (9, Wii),
+        (10, PlayStation),
+        (11, PlayStation2),
+        (12, PlayStation3),
+        (13, Psp),
+        (14, Xbox),
+        (15, Xbox360),
+        (16, Dreamcast),
+        (18, MegaDrive),
+        (23, Arcade),
+        (37, Mac),
+        (38, WiiU),
+        (41, GameBoyColor),
+        (4912, Nintendo3DS),
+        (4916, Android),
+        (4919, PlayStation4),
+        (4971, Switch),
+        (4980, Playstation5),
+        (4981, XboxSeries),
+    ])
+});
+
+impl TryFrom<Platform> for PlatformId {
+    type Error = anyhow::Error;
+
+    fn try_from(platform: Platform) -> Result<Self, Self::Error> {
+        let kv = MAPPING.iter().find(|(_, v)| v == &&platform);
+        let i = *kv.context("platform not supported by tgdb")?.0;
+        Ok(PlatformId(i))
+    }
+}
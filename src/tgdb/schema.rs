@@ -0,0 +1,99 @@
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Game {
+    pub id: u32,
+    pub game_title: String,
+    pub release_date: Option<String>,
+    pub players: Option<u32>,
+    pub overview: Option<String>,
+    pub developers: Option<Vec<u32>>,
+    pub genres: Option<Vec<u32>>,
+    pub publishers: Option<Vec<u32>>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Image {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub side: Option<String>,
+    pub filename: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GamesResponse {
+    pub data: GamesData,
+    pub include: Option<Include>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GamesData {
+    pub games: Vec<Game>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Include {
+    pub boxart: Option<ImageInclude>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ImageInclude {
+    #[serde(default, deserialize_with = "map_or_empty")]
+    pub data: HashMap<String, Vec<Image>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ImagesResponse {
+    pub data: ImagesData,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ImagesData {
+    #[serde(default, deserialize_with = "map_or_empty")]
+    pub images: HashMap<String, Vec<Image>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Named {
+    pub id: u32,
+    pub name: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct LookupResponse {
+    pub data: LookupData,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct LookupData {
+    #[serde(
+        alias = "genres",
+        alias = "developers",
+        alias = "publishers",
+        default,
+        deserialize_with = "map_or_empty"
+    )]
+    pub entries: HashMap<String, Named>,
+}
+
+// the API returns [] instead of {} when there are no entries
+fn map_or_empty<'de, D, T>(d: D) -> Result<HashMap<String, T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum MapOrSeq<T> {
+        Map(HashMap<String, T>),
+        Seq(Vec<IgnoredAny>),
+    }
+
+    match MapOrSeq::deserialize(d)? {
+        MapOrSeq::Map(m) => Ok(m),
+        MapOrSeq::Seq(_) => Ok(HashMap::new()),
+    }
+}
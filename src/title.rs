@@ -1,85 +1,344 @@
+use crate::collection::Collection;
+use crate::disc;
 use crate::prelude::*;
 
-static REGEXES: Lazy<[Regex; 8]> = Lazy::new(|| {
-    [
-        regex(r"\([^\)]+\)"),     // remove (...)
-        regex(r"/\[[^\]]+\]"),    // remove [...]
-        regex(r"^[0-9]+ \-"),     // remove 1234 -
-        regex(r"v[0-9\.]+"),      // remove v1.2
-        regex(r" +"),             // remove consecutive spaces
-        regex(r"(.*), ?The *$"),  // remove leading ", The"
-        regex(r"\w{4}\d{5}\-\["), // remove BLES00539-[
-        regex(r"\[.*"),           // remove [...
-    ]
-});
-
-pub fn prettify(path: &Path, title_map: &Option<HashMap<String, String>>) -> String {
-    let grandparent = path.parent().and_then(|p| p.parent());
-    let actual_path = match grandparent {
-        Some(gp) => {
-            let gp_name = gp
-                .file_stem()
-                .unwrap_or_default()
-                .to_string()
-                .to_ascii_uppercase();
-            if gp_name == "PS3_GAME" {
-                gp.parent().unwrap_or(path)
-            } else {
-                path
-            }
+// No-Intro region names
+static REGIONS: &[&str] = &[
+    "World",
+    "USA",
+    "Europe",
+    "Japan",
+    "Asia",
+    "Australia",
+    "Brazil",
+    "Canada",
+    "China",
+    "France",
+    "Germany",
+    "Hong Kong",
+    "Italy",
+    "Korea",
+    "Netherlands",
+    "Russia",
+    "Spain",
+    "Sweden",
+    "Taiwan",
+    "UK",
+];
+
+// TOSEC country codes, e.g. (US-EU)
+static COUNTRY_CODES: &[(&str, &str)] = &[
+    ("US", "USA"),
+    ("EU", "Europe"),
+    ("JP", "Japan"),
+    ("AU", "Australia"),
+    ("BR", "Brazil"),
+    ("CA", "Canada"),
+    ("CN", "China"),
+    ("FR", "France"),
+    ("DE", "Germany"),
+    ("HK", "Hong Kong"),
+    ("IT", "Italy"),
+    ("KR", "Korea"),
+    ("NL", "Netherlands"),
+    ("RU", "Russia"),
+    ("ES", "Spain"),
+    ("SE", "Sweden"),
+    ("TW", "Taiwan"),
+    ("GB", "UK"),
+];
+
+// GoodTools country codes, e.g. (UE)
+static LETTER_CODES: &[(char, &str)] = &[
+    ('W', "World"),
+    ('U', "USA"),
+    ('E', "Europe"),
+    ('J', "Japan"),
+    ('A', "Australia"),
+    ('B', "Brazil"),
+    ('C', "China"),
+    ('F', "France"),
+    ('G', "Germany"),
+    ('I', "Italy"),
+    ('K', "Korea"),
+    ('H', "Netherlands"),
+    ('S', "Spain"),
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Title {
+    pub title: String,
+    #[serde(default)]
+    pub regions: Vec<String>,
+    #[serde(default)]
+    pub languages: Vec<String>,
+    pub revision: Option<String>,
+    pub version: Option<String>,
+    #[serde(default)]
+    pub beta: bool,
+    #[serde(default)]
+    pub proto: bool,
+    #[serde(default)]
+    pub demo: bool,
+    pub disc: Option<u32>,
+    #[serde(default)]
+    pub dump_flags: Vec<String>,
+}
+
+// ordered find/replace pairs, overridable with `title_rules` in the config
+static DEFAULT_RULES: &[(&str, &str)] = &[
+    (r"^(Decrypted)+", ""),
+    (r"( \(ROM\))+$", ""),
+    (r"( \(ISO\))+$", ""),
+    (r"\]+$", ""),
+    (r"\[!\]", ""),
+    (r"\(U\)", ""),
+    (r"\(UE\)", ""),
+    (r"\(J\)", ""),
+    (r"\[C\]", ""),
+    (r"\[E\]", ""),
+    (r"\(M5\)", ""),
+    (r"\(beta\)", ""),
+    (r"Mega Man X ", "Mega Man X"),
+    (r"Bros\.", "Bros"),
+    (r"Pokemon", "Pokémon"),
+    (r"Legend of Zelda, The", "The Legend of Zelda"),
+    (r"\w{4}\d{5}\-\[", ""),      // remove BLES00539-[
+    (r"\([^\)]+\)", ""),          // remove (...)
+    (r"/\[[^\]]+\]", ""),         // remove [...]
+    (r"^[0-9]+ \-", ""),          // remove 1234 -
+    (r"v[0-9\.]+", ""),           // remove v1.2
+    (r" +", " "),                 // remove consecutive spaces
+    (r"(.*), ?The *$", "The $1"), // remove leading ", The"
+    (r"\[.*", ""),                // remove [...
+    (r"( ENC)+$", ""),
+    (r"(\[!)+$", ""),
+    (r"( Update)+$", ""),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "RuleDef", into = "RuleDef")]
+pub struct Rule {
+    find: Regex,
+    replace: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleDef {
+    find: String,
+    #[serde(default)]
+    replace: String,
+}
+
+impl TryFrom<RuleDef> for Rule {
+    type Error = regex::Error;
+
+    fn try_from(def: RuleDef) -> Result<Self, Self::Error> {
+        Ok(Self {
+            find: Regex::new(&def.find)?,
+            replace: def.replace,
+        })
+    }
+}
+
+impl From<Rule> for RuleDef {
+    fn from(rule: Rule) -> Self {
+        Self {
+            find: rule.find.as_str().to_owned(),
+            replace: rule.replace,
         }
-        None => path,
+    }
+}
+
+pub fn default_rules() -> Vec<Rule> {
+    DEFAULT_RULES
+        .iter()
+        .map(|(find, replace)| Rule {
+            find: regex(find),
+            replace: (*replace).to_owned(),
+        })
+        .collect()
+}
+
+// global rules followed by the collection's own, then its title map
+pub struct Normalizer<'a> {
+    rules: Vec<&'a Rule>,
+    title_map: Option<&'a HashMap<String, String>>,
+}
+
+impl<'a> Normalizer<'a> {
+    pub fn new(rules: &'a [Rule]) -> Self {
+        Self {
+            rules: rules.iter().collect(),
+            title_map: None,
+        }
+    }
+
+    pub fn for_collection(rules: &'a [Rule], collection: &'a Collection) -> Self {
+        let collection_rules = collection.title_rules.iter().flatten();
+        Self {
+            rules: rules.iter().chain(collection_rules).collect(),
+            title_map: collection.title_map.as_ref(),
+        }
+    }
+
+    pub fn prettify(&self, path: &Path) -> String {
+        let stem = path.file_stem().map(|f| f.to_string()).unwrap_or_default();
+        self.prettify_name(&stem)
+    }
+
+    pub fn prettify_name(&self, name: &str) -> String {
+        let t = self.apply_rules(name);
+        self.mapped(&t).unwrap_or(t)
+    }
+
+    // the header title, if there's one, as filenames are often cryptic, e.g. "zelda3";
+    // a title map entry for the filename always wins
+    pub fn resolve(&self, path: &Path, header_title: Option<&str>) -> String {
+        let stem = path.file_stem().map(|f| f.to_string()).unwrap_or_default();
+        let file_title = self.apply_rules(&stem);
+
+        if let Some(v) = self.mapped(&file_title) {
+            return v;
+        }
+
+        match header_title {
+            Some(t) => self.prettify_name(t),
+            None => file_title,
+        }
+    }
+
+    fn apply_rules(&self, name: &str) -> String {
+        let mut t = name.trim().to_owned();
+
+        for rule in &self.rules {
+            t = rule
+                .find
+                .replace_all(&t, rule.replace.as_str())
+                .trim()
+                .to_owned();
+        }
+
+        t
+    }
+
+    fn mapped(&self, title: &str) -> Option<String> {
+        self.title_map.and_then(|tm| tm.get(title)).cloned()
+    }
+
+    // e.g. "Legend of Zelda, The - The Minish Cap (Europe) (En,Fr,De,Es,It) (Rev 1)"
+    pub fn parse(&self, name: &str) -> Title {
+        parse(name, self.prettify_name(name))
+    }
+}
+
+fn parse(name: &str, title: String) -> Title {
+    let mut t = Title {
+        title,
+        disc: disc::number(Path::new(name)),
+        ..Default::default()
     };
 
-    let t = actual_path
-        .file_stem()
-        .map(|f| f.to_string())
-        .unwrap_or_default()
-        .trim_start_matches("Decrypted")
-        .trim_end_matches(" (ROM)")
-        .trim_end_matches(" (ISO)")
-        .trim_end_matches(']')
-        .replace("[!]", "")
-        .replace("(U)", "")
-        .replace("(UE)", "")
-        .replace("(J)", "")
-        .replace("[C]", "")
-        .replace("[E]", "")
-        .replace("(M5)", "")
-        .replace("(beta)", "")
-        .replace("Mega Man X ", "Mega Man X")
-        .replace("Bros.", "Bros")
-        .replace("Pokemon", "Pokémon")
-        .replace("Legend of Zelda, The", "The Legend of Zelda");
-
-    let t = REGEXES[6].replace_all(t.trim(), "");
-    let t = REGEXES[0].replace_all(t.trim(), "");
-    let t = REGEXES[1].replace_all(t.trim(), "");
-    let t = REGEXES[2].replace_all(t.trim(), "");
-    let t = REGEXES[3].replace_all(t.trim(), "");
-    let t = REGEXES[4].replace_all(t.trim(), " ");
-    let t = REGEXES[5].replace_all(t.trim(), "The $1");
-    let t = REGEXES[7].replace_all(t.trim(), "");
-
-    let t = t
-        .trim()
-        .trim_end_matches(" ENC")
-        .trim_end_matches("[!")
-        .trim_end_matches(" Update")
-        .trim()
-        .into();
-
-    if let Some(tm) = title_map {
-        for (k, v) in tm {
-            if &t == k {
-                return v.clone();
-            }
+    for tag in tags(name, '(', ')') {
+        let lowercase = tag.to_ascii_lowercase();
+
+        if let Some(regions) = regions(tag) {
+            t.regions.extend(regions);
+        } else if let Some(languages) = languages(tag) {
+            t.languages.extend(languages);
+        } else if let Some(revision) = tag.strip_prefix("Rev ") {
+            t.revision = Some(revision.to_owned());
+        } else if let Some(version) = version(tag) {
+            t.version = Some(version);
+        } else if lowercase.starts_with("beta") {
+            t.beta = true;
+        } else if lowercase.starts_with("proto") {
+            t.proto = true;
+        } else if ["demo", "sample", "kiosk"]
+            .iter()
+            .any(|d| lowercase.starts_with(d))
+        {
+            t.demo = true;
         }
     }
 
+    if t.version.is_none() {
+        t.version = name.split_whitespace().find_map(version);
+    }
+
+    t.dump_flags = tags(name, '[', ']').map(|tag| tag.to_owned()).collect();
+
     t
 }
 
+fn tags(name: &str, open: char, close: char) -> impl Iterator<Item = &str> {
+    name.split(open)
+        .skip(1)
+        .filter_map(move |s| s.split(close).next())
+}
+
+fn regions(tag: &str) -> Option<Vec<String>> {
+    let owned = |rs: Vec<&str>| Some(rs.into_iter().map(|r| r.to_owned()).collect());
+
+    let names: Vec<&str> = tag.split(", ").collect();
+    if names.iter().all(|r| REGIONS.contains(r)) {
+        return owned(names);
+    }
+
+    let codes: Option<Vec<&str>> = tag
+        .split('-')
+        .map(|c| COUNTRY_CODES.iter().find(|(k, _)| *k == c).map(|(_, v)| *v))
+        .collect();
+    if codes.is_some() {
+        return owned(codes?);
+    }
+
+    if tag.len() > 3 {
+        return None;
+    }
+
+    let letters: Option<Vec<&str>> = tag
+        .chars()
+        .map(|c| LETTER_CODES.iter().find(|(k, _)| *k == c).map(|(_, v)| *v))
+        .collect();
+    owned(letters?)
+}
+
+// (En,Fr,De) for No-Intro and (en-de) for TOSEC
+fn languages(tag: &str) -> Option<Vec<String>> {
+    let is_language = |l: &str| {
+        let bytes = l.as_bytes();
+        bytes.len() == 2 && bytes[0].is_ascii_uppercase() && bytes[1].is_ascii_lowercase()
+    };
+
+    let languages: Vec<&str> = tag.split(',').collect();
+    if languages.iter().all(|l| is_language(l)) {
+        return Some(languages.into_iter().map(|l| l.to_owned()).collect());
+    }
+
+    let languages: Vec<&str> = tag.split('-').collect();
+    let is_lowercase = |l: &&str| l.len() == 2 && l.bytes().all(|b| b.is_ascii_lowercase());
+    if languages.iter().all(is_lowercase) {
+        let capitalized = languages
+            .into_iter()
+            .map(|l| l[..1].to_ascii_uppercase() + &l[1..])
+            .collect();
+        return Some(capitalized);
+    }
+
+    None
+}
+
+// v1.1 -> 1.1
+fn version(s: &str) -> Option<String> {
+    let version = s.strip_prefix('v')?;
+    if !version.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(version.to_owned())
+}
+
 fn regex(re: &str) -> Regex {
     Regex::new(re).expect("invalid regex")
 }
@@ -87,8 +346,44 @@ fn regex(re: &str) -> Regex {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::header;
+    use crate::platform::Platform;
     use walkdir::WalkDir;
 
+    #[test]
+    fn test_parse() {
+        let rules = default_rules();
+        let normalizer = Normalizer::new(&rules);
+        let parse = |name| normalizer.parse(name);
+
+        let t = parse("Legend of Zelda, The - The Minish Cap (Europe) (En,Fr,De,Es,It) (Rev 1)");
+        assert_eq!(t.title, "The Legend of Zelda - The Minish Cap");
+        assert_eq!(t.regions, ["Europe"]);
+        assert_eq!(t.languages, ["En", "Fr", "De", "Es", "It"]);
+        assert_eq!(t.revision.as_deref(), Some("1"));
+
+        let t = parse("Final Fantasy VII (USA, Europe) (Disc 2)");
+        assert_eq!(t.regions, ["USA", "Europe"]);
+        assert_eq!(t.disc, Some(2));
+
+        let t = parse("Star Fox 2 (J) (beta) [T+Eng1.00_AGTP]");
+        assert_eq!(t.title, "Star Fox 2");
+        assert_eq!(t.regions, ["Japan"]);
+        assert!(t.beta);
+        assert_eq!(t.dump_flags, ["T+Eng1.00_AGTP"]);
+
+        let t = parse("Sonic the Hedgehog (1991)(Sega)(US-EU)(en-de)(Demo)[!]");
+        assert_eq!(t.regions, ["USA", "Europe"]);
+        assert_eq!(t.languages, ["En", "De"]);
+        assert!(t.demo);
+        assert_eq!(t.dump_flags, ["!"]);
+
+        let t = parse("Pokemon FireRed v1.1 (UE)");
+        assert_eq!(t.regions, ["USA", "Europe"]);
+        assert_eq!(t.version.as_deref(), Some("1.1"));
+        assert!(!t.proto);
+    }
+
     #[test]
     fn test_prettify() {
         let dir = PathBuf::from("./fixtures/ROMs");
@@ -99,12 +394,31 @@ mod tests {
 
         let allowlist = ["zip", "rar", "7z", "iso", "cue", "bin", "3ds", "cia"];
 
-        let title_map = {
-            let mut m = HashMap::new();
-            m.insert("mvsc".to_owned(), "Marvel vs Capcom".to_owned());
-            Some(m)
+        let collection = Collection {
+            name: "Arcade".into(),
+            path: PathBuf::from("/path/to/ROMs/arcade"),
+            extensions: vec!["zip".into()],
+            platform: Platform::Arcade,
+            provider: Default::default(),
+            denylist: None,
+            dats: None,
+            m3u: false,
+            title_rules: Some(vec![Rule {
+                find: regex("Drakes"),
+                replace: "Drake's".into(),
+            }]),
+            title_map: Some(HashMap::from([(
+                "mvsc".to_owned(),
+                "Marvel vs Capcom".to_owned(),
+            )])),
+            id_map: None,
+            retroarch_core: None,
+            launch: "retroarch {file}".into(),
         };
 
+        let rules = default_rules();
+        let normalizer = Normalizer::for_collection(&rules, &collection);
+
         let mut output = vec![];
         for entry in entries {
             let path = entry.path();
@@ -114,7 +428,8 @@ mod tests {
                 .to_string()
                 .to_ascii_lowercase();
             if allowlist.contains(&extension.as_str()) {
-                output.push(prettify(path, &title_map));
+                let path = header::ps3::game_dir(path).unwrap_or(path);
+                output.push(normalizer.prettify(path));
             }
         }
 
@@ -128,6 +443,7 @@ mod tests {
                 "Dragon Ball Z - Budokai Tenkaichi 3",
                 "Dragon Ball Z - Tenkaichi Tag Team",
                 "Elite Beat Agents",
+                "Final Fantasy VII",
                 "Fullmetal Alchemist Stray Rondo",
                 "Ghost Trick - Phantom Detective",
                 "God of War - Chains of Olympus",
@@ -164,8 +480,39 @@ mod tests {
                 "The Legend of Zelda - The Minish Cap",
                 "The Legend of Zelda - The Wind Waker",
                 "The World Ends With You",
-                "Uncharted 3 Drakes Deception",
+                "Uncharted 3 Drake's Deception",
             ]
         );
     }
+
+    #[test]
+    fn test_resolve() {
+        let rules = default_rules();
+        let title_map = HashMap::from([("SLUS-00594".to_owned(), "Metal Gear Solid".to_owned())]);
+        let normalizer = Normalizer {
+            rules: rules.iter().collect(),
+            title_map: Some(&title_map),
+        };
+
+        assert_eq!(
+            normalizer.resolve(Path::new("zelda3.sfc"), Some("The Legend Of Zelda")),
+            "The Legend Of Zelda"
+        );
+        assert_eq!(
+            normalizer.resolve(Path::new("Super Mario 64 (USA).z64"), None),
+            "Super Mario 64"
+        );
+        assert_eq!(
+            normalizer.resolve(Path::new("SLUS-00561.cue"), Some("Castlevania")),
+            "Castlevania"
+        );
+        assert_eq!(
+            normalizer.resolve(Path::new("0042.nds"), Some("Elite Beat Agents")),
+            "Elite Beat Agents"
+        );
+        assert_eq!(
+            normalizer.resolve(Path::new("SLUS-00594.cue"), Some("METAL GEAR")),
+            "Metal Gear Solid"
+        );
+    }
 }
@@ -1,4 +1,6 @@
 use crate::collection::Collection;
+use crate::descriptor::MissingTracks;
+use crate::http::Offline;
 use crate::prelude::*;
 use std::sync::mpsc;
 use workerpool::thunk::{Thunk, ThunkWorker};
@@ -17,9 +19,14 @@ struct FullWorkerResult {
     res: WorkerResult,
 }
 
-pub trait FileWorker: Runnable {
+pub trait FileWorker: Runnable + Clone + Send + 'static {
     fn get_filepaths(&self, collections: &[Collection]) -> Result<HashMap<usize, Vec<PathBuf>>>;
-    fn process(collection: &Collection, file: &Path, system: &System) -> Result<WorkerResult>;
+    fn process(
+        &self,
+        collection: &Collection,
+        file: &Path,
+        system: &System,
+    ) -> Result<WorkerResult>;
 }
 
 pub fn run<I: FileWorker>(input: &I, system: System) -> Result<()> {
@@ -47,30 +54,26 @@ pub fn run<I: FileWorker>(input: &I, system: System) -> Result<()> {
                 .clone();
 
             let new_system_arc = Arc::clone(&system_arc);
+            let input = input.clone();
 
             pool.execute_to(
                 tx.clone(),
                 Thunk::of(move || {
                     let collection_name = collection.name.clone();
-                    match I::process(&collection, &file, new_system_arc.as_ref()) {
+                    match input.process(&collection, &file, new_system_arc.as_ref()) {
                         Ok(res) => FullWorkerResult {
                             collection: collection_name,
                             res,
                             error: None,
                         },
-                        Err(e) => {
-                            if e.to_string().contains("Too Many Requests") {
-                                panic!("aborting because of too many requests")
-                            }
-                            FullWorkerResult {
-                                collection: collection_name,
-                                res: WorkerResult {
-                                    entry: file.to_string(),
-                                    progress: (0, 0),
-                                },
-                                error: Some(e),
-                            }
-                        }
+                        Err(e) => FullWorkerResult {
+                            collection: collection_name,
+                            res: WorkerResult {
+                                entry: file.to_string(),
+                                progress: (0, 0),
+                            },
+                            error: Some(e),
+                        },
                     }
                 }),
             )
@@ -78,6 +81,8 @@ pub fn run<I: FileWorker>(input: &I, system: System) -> Result<()> {
     }
 
     let mut errors = 0;
+    let mut missing_tracks = 0;
+    let mut offline = 0;
     let results = rx.iter().take(n_files);
 
     for result in results {
@@ -98,17 +103,53 @@ pub fn run<I: FileWorker>(input: &I, system: System) -> Result<()> {
             format!("{}%", sum)
         };
 
-        if let Some(e) = error {
+        if matches!(&error, Some(e) if caused_by::<Offline>(e)) {
+            offline += 1;
+            info!(collection, entry, status = "skipped: offline");
+        } else if let Some(e) = error {
             errors += 1;
-            info!(collection, entry, error = e.to_string());
+            let status = if caused_by::<MissingTracks>(&e) {
+                missing_tracks += 1;
+                "missing tracks"
+            } else {
+                "failed"
+            };
+            info!(collection, entry, status, error = e.to_string());
         } else {
             info!(progress, collection, entry);
         }
     }
 
-    if errors > 0 {
+    if offline > 0 {
+        info!(msg = "skipped: offline", count = offline);
+    }
+
+    if missing_tracks > 0 {
+        Err(anyhow!(
+            "{} files failed, {} of them with missing tracks",
+            errors,
+            missing_tracks
+        ))
+    } else if errors > 0 {
         Err(anyhow!("{} files failed", errors))
     } else {
         Ok(())
     }
 }
+
+// errors may have been given context on their way up
+fn caused_by<E: std::error::Error + Send + Sync + 'static>(e: &Error) -> bool {
+    e.chain().any(|cause| cause.downcast_ref::<E>().is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_caused_by() {
+        let e = Error::from(Offline).context("unable to fetch games");
+        assert!(caused_by::<Offline>(&e));
+        assert!(!caused_by::<MissingTracks>(&e));
+    }
+}
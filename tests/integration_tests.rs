@@ -18,7 +18,7 @@ fn test_e2e() {
     let (fixtures_dir, testdata_dir) = copy_roms().expect("copy_roms failed");
     set_home_dir(&testdata_dir).expect("set_home_dir failed");
     let server = MockServer::start();
-    let config_path = set_config(&server, &testdata_dir).expect("set_config failed");
+    let config_path = set_config(&server, &fixtures_dir, &testdata_dir).expect("set_config failed");
 
     let runner = Runner {
         server,
@@ -29,8 +29,16 @@ fn test_e2e() {
 
     runner.test_config().expect("test_config failed");
     runner.test_scrape().expect("test_scrape failed");
+    runner.test_match().expect("test_match failed");
+    runner.test_refresh().expect("test_refresh failed");
+    runner.test_offline().expect("test_offline failed");
     runner.test_media().expect("test_media failed");
     runner.test_pegasus().expect("test_pegasus failed");
+    runner.test_es().expect("test_es failed");
+    runner.test_retroarch().expect("test_retroarch failed");
+    runner.test_launchbox().expect("test_launchbox failed");
+    runner.test_attract().expect("test_attract failed");
+    runner.test_report().expect("test_report failed");
 }
 
 fn copy_roms() -> Result<(PathBuf, PathBuf)> {
@@ -81,7 +89,7 @@ fn set_home_dir(testdata_dir: &Path) -> Result<()> {
     Ok(())
 }
 
-fn set_config(server: &MockServer, testdata_dir: &Path) -> Result<String> {
+fn set_config(server: &MockServer, fixtures_dir: &Path, testdata_dir: &Path) -> Result<String> {
     let base_url = &server.base_url();
 
     let config = format!(
@@ -97,6 +105,15 @@ igdb:
   images_base_url: {base_url}
   denylisted_name_substrings:
     - bad game
+  min_confidence: 0
+  requests_per_second: 100
+tgdb:
+  api_key: test_api_key
+  api_base_url: {base_url}
+  images_base_url: {base_url}
+  denylisted_name_substrings:
+    - bad game
+  min_confidence: 0
 collections:
   - name: Game Boy Advance
     path: {testdata}/ROMs/gba
@@ -105,6 +122,11 @@ collections:
     platform: gba
     denylist:
       - Link to the Past
+    id_map:
+      Pokemon FireRed.zip: 1035
+    retroarch_core:
+      path: /cores/mgba_libretro.so
+      name: mGBA
     launch: retroarch --gba ${{file}}
   - name: Nintendo 3DS
     path: {testdata}/ROMs/3ds
@@ -123,13 +145,38 @@ collections:
     platform: ps3
     denylist: ~
     launch: retroarch --ps3 ${{file}}
+  - name: Playstation
+    path: {testdata}/ROMs/ps1
+    extensions: 
+      - cue
+      - bin
+    platform: ps1
+    m3u: true
+    launch: retroarch --ps1 ${{file}}
+  - name: Game Boy Color
+    path: {testdata}/ROMs/gbc
+    extensions: 
+      - zip
+    platform: gbc
+    provider: tgdb
+    launch: retroarch --gbc ${{file}}
+  - name: Game Boy
+    path: {testdata}/ROMs/gb
+    extensions: 
+      - gb
+    platform: gb
+    provider: dat
+    dats:
+      - {fixtures}/dats/gb.dat
+    launch: retroarch --gb ${{file}}
 tracing:
   time: false
   level: {bin}=trace
 "#,
         bin = PROJECT_NAME,
         base_url = base_url,
-        testdata = testdata_dir.to_string()
+        testdata = testdata_dir.to_string(),
+        fixtures = fixtures_dir.to_string()
     );
 
     let config_path = testdata_dir.join("config.yaml");
@@ -186,13 +233,66 @@ impl Runner {
                 .body(json);
         });
 
+        let mut multiquery_mock = self.multiquery_mock(json);
+
+        let tgdb_games_json = include_str!("../fixtures/responses/tgdb_games.json");
+        let tgdb_images_json = include_str!("../fixtures/responses/tgdb_images.json");
+
+        let mut tgdb_games_mock = self.server.mock(|when, then| {
+            when.method(GET)
+                .path_contains("Games/ByGameName")
+                .query_param("apikey", "test_api_key");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(tgdb_games_json);
+        });
+
+        let mut tgdb_images_mock = self.server.mock(|when, then| {
+            when.method(GET)
+                .path_contains("Games/Images")
+                .query_param("games_id", "3035");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(tgdb_images_json);
+        });
+
+        let mut tgdb_lookup_mocks: Vec<_> = [
+            ("Genres", r#""genres": { "1": { "id": 1, "name": "Action" }, "2": { "id": 2, "name": "Adventure" } }"#),
+            ("Developers", r#""developers": { "2": { "id": 2, "name": "Capcom" }, "3": { "id": 3, "name": "Flagship" } }"#),
+            ("Publishers", r#""publishers": { "1": { "id": 1, "name": "Nintendo" } }"#),
+        ]
+        .iter()
+        .map(|(endpoint, entries)| {
+            let body = format!(r#"{{ "code": 200, "data": {{ "count": 2, {} }} }}"#, entries);
+            self.server.mock(|when, then| {
+                when.method(GET).path_contains(*endpoint);
+                then.status(200)
+                    .header("content-type", "application/json")
+                    .body(body);
+            })
+        })
+        .collect();
+
         self.call(&["scrape"])?;
 
         twitch_mock.assert_hits(1);
-        igdb_mock.assert_hits(12);
+        // 13 searches, batched, plus 1 pinned game
+        multiquery_mock.assert_hits(2);
+        igdb_mock.assert_hits(1);
+        tgdb_games_mock.assert_hits(4);
+        // the games share an id, so the other 3 find its images in the cache
+        tgdb_images_mock.assert_hits(1);
 
         twitch_mock.delete();
+        multiquery_mock.delete();
         igdb_mock.delete();
+        tgdb_games_mock.delete();
+        tgdb_images_mock.delete();
+
+        for mock in tgdb_lookup_mocks.iter_mut() {
+            mock.assert_hits(1);
+            mock.delete();
+        }
 
         let meta = self.testdata_dir.join(PathBuf::from_iter(&[
             "ROMs",
@@ -202,6 +302,136 @@ impl Runner {
         ]));
         assert!(meta.exists());
 
+        let meta = self.testdata_dir.join(PathBuf::from_iter(&[
+            "ROMs",
+            "gbc",
+            "meta",
+            "The_Legend_of_Zelda_Oracle_of_Seasons_zip_meta.yaml",
+        ]));
+        assert!(meta.exists());
+
+        let playlist = self.testdata_dir.join(PathBuf::from_iter(&[
+            "ROMs",
+            "ps1",
+            "Final Fantasy VII (USA).m3u",
+        ]));
+        assert_eq!(
+            fs::read_to_string(playlist)?,
+            "Final Fantasy VII (USA) (Disc 1).cue\nFinal Fantasy VII (USA) (Disc 2).cue\n"
+        );
+
+        Ok(())
+    }
+
+    fn test_match(&self) -> Result<()> {
+        let json = include_str!("../fixtures/responses/metadata.json");
+
+        let mut igdb_mock = self.server.mock(|when, then| {
+            when.method(POST)
+                .path_contains("games")
+                .body_contains("where id = 1035;");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(json);
+        });
+
+        let gba_dir = self.testdata_dir.join(PathBuf::from_iter(&["ROMs", "gba"]));
+        let rom = gba_dir.join("The Legend of Zelda - The Minish Cap.zip");
+        self.call(&["match", &rom.to_string(), "1035"])?;
+
+        // cached when the id_map pin was scraped
+        igdb_mock.assert_hits(0);
+        igdb_mock.delete();
+
+        for name in ["The_Legend_of_Zelda_The_Minish_Cap", "Pokemon_FireRed"] {
+            let meta = gba_dir.join("meta").join(format!("{}_zip_meta.yaml", name));
+            let meta = fs::read_to_string(meta)?;
+            assert!(meta.contains("name: __NAME__"));
+            // quoted, as it would otherwise be read back as a number
+            assert!(meta.contains("pin: '1035'"));
+        }
+
+        Ok(())
+    }
+
+    fn test_refresh(&self) -> Result<()> {
+        let json = include_str!("../fixtures/responses/metadata.json");
+
+        let mut search_mock = self.server.mock(|when, then| {
+            when.method(POST)
+                .path_contains("games")
+                .body_contains("search");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(json);
+        });
+
+        let mut multiquery_mock = self.multiquery_mock(json);
+
+        let mut pin_mock = self.server.mock(|when, then| {
+            when.method(POST)
+                .path_contains("games")
+                .body_contains("where id = 1035;");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(json);
+        });
+
+        self.call(&[
+            "scrape",
+            "--refresh",
+            "--collection",
+            "Game Boy Advance",
+            "--glob",
+            "*.zip",
+        ])?;
+
+        // pinned games are fetched by id
+        multiquery_mock.assert_hits(1);
+        search_mock.assert_hits(0);
+        pin_mock.assert_hits(2);
+
+        multiquery_mock.delete();
+        search_mock.delete();
+        pin_mock.delete();
+
+        Ok(())
+    }
+
+    fn test_offline(&self) -> Result<()> {
+        let mut mock = self.server.mock(|when, then| {
+            when.method(POST);
+            then.status(500);
+        });
+
+        // cached by the refresh
+        let output = self.call_process(&[
+            "--offline",
+            "scrape",
+            "--refresh",
+            "--collection",
+            "Game Boy Advance",
+            "--glob",
+            "*.zip",
+        ])?;
+        assert!(!output.contains("skipped: offline"));
+
+        // uncached games are skipped rather than failed
+        let output = self.call_process(&[
+            "--offline",
+            "--no-cache",
+            "scrape",
+            "--refresh",
+            "--collection",
+            "Game Boy Advance",
+        ])?;
+        assert!(output.contains(r#"entry="Pokémon Emerald.zip" status="skipped: offline""#));
+        // the denylisted one isn't looked up at all
+        assert!(output.contains(r#"msg="skipped: offline" count=4"#));
+
+        mock.assert_hits(0);
+        mock.delete();
+
         Ok(())
     }
 
@@ -222,7 +452,7 @@ impl Runner {
 
         self.call(&["media", "download"])?;
 
-        mock.assert_hits(36);
+        mock.assert_hits(54);
         mock.delete();
 
         let meta = self
@@ -238,6 +468,19 @@ impl Runner {
             .join("The_Legend_of_Zelda_The_Minish_Cap_zip_screenshot.jpg")
             .exists());
 
+        let meta = self
+            .testdata_dir
+            .join(PathBuf::from_iter(&["ROMs", "gbc", "meta"]));
+        assert!(meta
+            .join("The_Legend_of_Zelda_Oracle_of_Seasons_zip_background.jpg")
+            .exists());
+        assert!(meta
+            .join("The_Legend_of_Zelda_Oracle_of_Seasons_zip_boxFront.jpg")
+            .exists());
+        assert!(meta
+            .join("The_Legend_of_Zelda_Oracle_of_Seasons_zip_screenshot.jpg")
+            .exists());
+
         Ok(())
     }
 
@@ -263,18 +506,221 @@ impl Runner {
             ],
         )?;
 
-        // TODO
+        self.assert_meta(
+            "gbc",
+            &[
+                "game: __TGDB_NAME__",
+                "file: ../The Legend of Zelda - Oracle of Seasons.zip",
+                "release: 2001-05-14",
+                "genres: Action, Adventure",
+                "assets.boxFront: The_Legend_of_Zelda_Oracle_of_Seasons_zip_boxFront.jpg",
+                "developers: Capcom, Flagship",
+                "publishers: Nintendo",
+                "players: 2",
+                "summary: This game is super fun",
+            ],
+        )?;
+
+        self.assert_meta(
+            "gb",
+            &[
+                "game: Tetris",
+                "file: ../tetris.gb",
+                "game: Super Mario Land",
+                "file: ../sml.gb",
+            ],
+        )?;
+
+        self.assert_meta(
+            "ps1",
+            &[
+                "file: ../Final Fantasy VII (USA).m3u",
+                "assets.boxFront: Final_Fantasy_VII_USA_m3u_boxFront.jpg",
+                "file: ../Mega Man X 4 (USA).cue",
+            ],
+        )?;
+
         self.assert_meta(
             "ps3",
             &[
-                "file: ../BCES01175-[Uncharted 3 Drakes Deception]/PS3_GAME/USRDIR/EBOOT.BIN",
-                "assets.boxFront: BCES01175_Uncharted_3_Drakes_Deception_PS3_GAME_USRDIR_EBOOT_BIN_boxFront.jpg",
+                "file: ../BCES01175-[Uncharted 3 Drakes Deception]\n",
+                "assets.boxFront: BCES01175_Uncharted_3_Drake_s_Deception_boxFront.jpg",
+                "file: ../BLUS31604-[Persona 5]\n",
+                "assets.boxFront: BLUS31604_Persona_5_boxFront.jpg",
             ],
         )?;
 
         Ok(())
     }
 
+    fn test_es(&self) -> Result<()> {
+        self.call(&["es", "gen"])?;
+
+        let gamelist_path =
+            self.testdata_dir
+                .join(PathBuf::from_iter(&["ROMs", "gba", "gamelist.xml"]));
+        let gamelist = fs::read_to_string(&gamelist_path)?;
+        assert!(gamelist.contains("<path>./Pokemon FireRed.zip</path>"));
+        assert!(gamelist.contains("<name>__NAME__</name>"));
+        assert!(gamelist.contains("<thumbnail>./meta/Pokemon_FireRed_zip_boxFront.jpg</thumbnail>"));
+        assert!(gamelist.contains("<fanart>./meta/Pokemon_FireRed_zip_background.jpg</fanart>"));
+        assert!(!gamelist.contains("<marquee>"));
+
+        // as if ES had written it
+        let played = gamelist.replacen("</game>", "<playcount>7</playcount></game>", 1);
+        fs::write(&gamelist_path, played)?;
+
+        self.call(&["es", "gen"])?;
+
+        let gamelist = fs::read_to_string(&gamelist_path)?;
+        assert!(gamelist.contains("<playcount>7</playcount>"));
+
+        Ok(())
+    }
+
+    fn test_retroarch(&self) -> Result<()> {
+        let retroarch_dir = self.testdata_dir.join("retroarch");
+        self.call(&["retroarch", "gen", "--dir", &retroarch_dir.to_string()])?;
+
+        let lpl = fs::read_to_string(retroarch_dir.join(PathBuf::from_iter(&[
+            "playlists",
+            "Nintendo - Game Boy Advance.lpl",
+        ])))?;
+        assert!(lpl.contains(r#""core_name": "mGBA""#));
+        assert!(lpl.contains(r#""db_name": "Nintendo - Game Boy Advance.rdb""#));
+        assert!(lpl.contains("Pokemon FireRed.zip"));
+
+        let boxart = retroarch_dir.join(PathBuf::from_iter(&[
+            "thumbnails",
+            "Nintendo - Game Boy Advance",
+            "Named_Boxarts",
+            "__NAME__.png",
+        ]));
+        assert!(fs::read(boxart)?.starts_with(b"\x89PNG"));
+
+        Ok(())
+    }
+
+    fn test_launchbox(&self) -> Result<()> {
+        let launchbox_dir = self.testdata_dir.join("launchbox");
+        self.call(&["launchbox", "gen", "--dir", &launchbox_dir.to_string()])?;
+
+        let xml = fs::read_to_string(launchbox_dir.join(PathBuf::from_iter(&[
+            "Data",
+            "Platforms",
+            "Nintendo Game Boy Advance.xml",
+        ])))?;
+        assert!(xml.contains("<Platform>Nintendo Game Boy Advance</Platform>"));
+        assert!(xml.contains("<Genre>Role-playing (RPG); Adventure</Genre>"));
+        assert!(xml.contains("<CommunityStarRating>4.5</CommunityStarRating>"));
+        assert!(xml.contains("Pokemon FireRed.zip</ApplicationPath>"));
+
+        let box_front = launchbox_dir.join(PathBuf::from_iter(&[
+            "Images",
+            "Nintendo Game Boy Advance",
+            "Box - Front",
+            "__NAME__-02.jpg",
+        ]));
+        assert!(box_front.exists());
+
+        Ok(())
+    }
+
+    fn test_attract(&self) -> Result<()> {
+        let attract_dir = self.testdata_dir.join("attract");
+        self.call(&["attract", "gen", "--dir", &attract_dir.to_string()])?;
+
+        let romlist = fs::read_to_string(
+            attract_dir.join(PathBuf::from_iter(&["romlists", "Game Boy Advance.txt"])),
+        )?;
+        assert!(romlist.starts_with("#Name;Title;Emulator;"));
+        assert!(romlist.contains(
+            "Pokemon FireRed;__NAME__;Game Boy Advance;;2004;Nintendo;Role-playing (RPG) / Adventure;2;"
+        ));
+
+        let cfg = fs::read_to_string(
+            attract_dir.join(PathBuf::from_iter(&["emulators", "Game Boy Advance.cfg"])),
+        )?;
+        assert!(cfg.contains("executable           retroarch"));
+        assert!(cfg.contains("romext               .zip"));
+
+        // game folders keep their whole name
+        let romlist = fs::read_to_string(
+            attract_dir.join(PathBuf::from_iter(&["romlists", "Playstation 3.txt"])),
+        )?;
+        assert!(romlist.contains("\nBCES01175-[Uncharted 3 Drakes Deception];"));
+        let cfg = fs::read_to_string(
+            attract_dir.join(PathBuf::from_iter(&["emulators", "Playstation 3.cfg"])),
+        )?;
+        assert!(cfg.contains("romext               .bin;<DIR>"));
+
+        let flyer = attract_dir.join(PathBuf::from_iter(&[
+            "scraper",
+            "Game Boy Advance",
+            "flyer",
+            "Pokemon FireRed.jpg",
+        ]));
+        assert!(flyer.exists());
+
+        Ok(())
+    }
+
+    fn test_report(&self) -> Result<()> {
+        self.call(&["report"])?;
+        self.call(&["report", "--format", "csv", "--confidence-below", "0.5"])?;
+
+        let rows = self.report_json()?;
+        let status = |rom: &str| {
+            rows.iter()
+                .find(|r| r["rom"] == rom)
+                .map(|r| r["status"].as_str().unwrap_or_default().to_owned())
+                .unwrap_or_else(|| panic!("{} isn't in the report", rom))
+        };
+
+        assert_eq!(
+            status("The Legend of Zelda - A Link to the Past.zip"),
+            "denylisted"
+        );
+        assert_eq!(status("Pokemon FireRed.zip"), "matched");
+        assert_eq!(status("Monster Hunter 4 Ultimate.cia"), "low-confidence");
+        assert_eq!(status("tetris.gb"), "matched");
+
+        Ok(())
+    }
+
+    fn report_json(&self) -> Result<Vec<serde_json::Value>> {
+        let stdout = self.call_process(&["report", "--format", "json"])?;
+        Ok(serde_json::from_str(&stdout)?)
+    }
+
+    // like call, but in a separate process, to capture what's printed and logged
+    fn call_process(&self, extra_args: &[&str]) -> Result<String> {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_louvre"))
+            .args(["--config", &self.config_path])
+            .args(extra_args)
+            .output()?;
+        assert!(output.status.success());
+
+        let stdout = String::from_utf8(output.stdout)?;
+        let ansi = regex::Regex::new(r"\x1b\[[0-9;]*m")?;
+        Ok(ansi.replace_all(&stdout, "").into_owned())
+    }
+
+    // the same results for every named query
+    fn multiquery_mock(&self, json: &str) -> httpmock::Mock<'_> {
+        let results: Vec<String> = (0..10)
+            .map(|i| format!(r#"{{ "name": "{}", "result": {} }}"#, i, json))
+            .collect();
+        let body = format!("[{}]", results.join(","));
+
+        self.server.mock(|when, then| {
+            when.method(POST).path_contains("multiquery");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(body);
+        })
+    }
+
     fn assert_meta(&self, platform: &str, substrs: &[&str]) -> Result<()> {
         let meta_path = self.testdata_dir.join(PathBuf::from_iter(&[
             "ROMs",